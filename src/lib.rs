@@ -4,10 +4,17 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-#![forbid(unsafe_code)]
+// `bytes::BufMut` is an `unsafe` trait, so the `bytes-mut` feature allows `unsafe`
+// in the module implementing it only.
+#![cfg_attr(not(feature = "bytes-mut"), forbid(unsafe_code))]
+#![cfg_attr(feature = "bytes-mut", deny(unsafe_code))]
 
+use std::borrow::Cow;
 use std::cmp::min;
-use std::io::{Error, ErrorKind, Read, Result, Write};
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{Error, ErrorKind, IoSlice, Read, Result, Write};
+use std::mem::MaybeUninit;
 
 macro_rules! check_valid {
     ($self:ident) => {
@@ -16,6 +23,29 @@ macro_rules! check_valid {
     }
 }
 
+#[cfg(feature = "memchr")]
+use memchr::{memchr, memrchr};
+
+#[cfg(not(feature = "memchr"))]
+fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().position(|&b| b == needle)
+}
+
+#[cfg(not(feature = "memchr"))]
+fn memrchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    haystack.iter().rposition(|&b| b == needle)
+}
+
+#[cfg(feature = "memchr")]
+fn memchr_count(needle: u8, haystack: &[u8]) -> usize {
+    memchr::memchr_iter(needle, haystack).count()
+}
+
+#[cfg(not(feature = "memchr"))]
+fn memchr_count(needle: u8, haystack: &[u8]) -> usize {
+    haystack.iter().filter(|&&b| b == needle).count()
+}
+
 /// A fixed sized buffer connected end-to-end.
 ///
 /// # Examples
@@ -38,15 +68,196 @@ macro_rules! check_valid {
 /// rb.write(&[4, 5, 6, 7, 8]).unwrap();
 /// assert_eq!(rb.len(), 5);
 /// ```
+///
+/// Generic I/O code can borrow a ring buffer instead of taking ownership of it,
+/// as `&mut RingBuf` implements `Read` and `Write` through the standard library's
+/// blanket impls for `&mut R` and `&mut W`:
+///
+/// ```
+/// use std::io::{self, Read, Write};
+///
+/// use bytebufrs::RingBuf;
+///
+/// fn greet(mut w: impl Write) -> io::Result<()> {
+///     w.write_all(b"hello")
+/// }
+///
+/// let mut rb = RingBuf::with_capacity(10);
+/// greet(&mut rb).unwrap();
+/// greet(&mut rb).unwrap();
+/// assert_eq!(rb.len(), 10);
+///
+/// let mut buf = String::new();
+/// (&mut rb).take(5).read_to_string(&mut buf).unwrap();
+/// assert_eq!(buf, "hello");
+/// assert_eq!(rb.len(), 5);
+/// ```
 pub struct RingBuf {
     data: Box<[u8]>,
     read_pos: usize,
-    write_pos: usize
+    write_pos: usize,
+    policy: FullPolicy,
+    read_bit_pos: u8,
+    write_bit_buf: u8,
+    write_bit_len: u8,
+    consumed: usize,
+    total_read: usize,
+    high_water_mark: usize,
+    panic_on_truncate: bool,
+    zero_on_clear: bool,
+    reset_on_empty: bool,
+    growth_factor: f32,
+    compact_threshold: f32,
+    event_handler: Option<Box<dyn FnMut(BufEvent) + Send + Sync>>
+}
+
+/// Controls what writing to a [`RingBuf`] does when the data doesn't fit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FullPolicy {
+    /// Only write as many bytes as fit, resulting in a short write.
+    /// This is the default.
+    #[default]
+    Reject,
+    /// Drop the oldest bytes in the ring buffer to make room for the new ones.
+    /// If the data is larger than the capacity, only its last `capacity()` bytes are kept.
+    OverwriteOldest,
+    /// Grow the underlying buffer so all data fits.
+    /// Writes never return `Ok(0)` for non-empty data, so `write_all` always succeeds.
+    Grow
+}
+
+/// A change in the fullness of a [`RingBuf`], reported to its event handler.
+///
+/// See [`RingBuf::set_event_handler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufEvent {
+    /// A write filled the ring buffer.
+    BecameFull,
+    /// A read emptied the ring buffer.
+    BecameEmpty
+}
+
+/// The specific reason an operation on a [`RingBuf`] failed.
+///
+/// Methods of `RingBuf` return `std::io::Error`s so they compose with `Read` and `Write`;
+/// when the failure is caused by the contents, free space or positions of the ring buffer,
+/// the `io::Error` wraps a `RingError` which can be recovered with `get_ref` and `downcast_ref`.
+///
+/// Other failures are reported as plain `io::Error`s: invalid arguments that don't depend on
+/// the ring buffer, like reading more than 64 bits at once or an empty delimiter, invalid UTF-8,
+/// `ErrorKind::WouldBlock` from the `*_or_would_block` methods and errors of the readers
+/// and writers passed in.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::{ErrorKind, Write};
+///
+/// use bytebufrs::{RingBuf, RingError};
+///
+/// let mut rb = RingBuf::with_capacity(2);
+/// rb.write(&[0, 1]).unwrap();
+///
+/// let err = rb.extend_from_slice_exact(&[2]).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::WriteZero);
+/// assert_eq!(err.get_ref().unwrap().downcast_ref::<RingError>(), Some(&RingError::Full));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RingError {
+    /// The data is larger than the capacity of the ring buffer.
+    /// Maps to `ErrorKind::WriteZero`.
+    Overflow,
+    /// There's not enough free space for the data.
+    /// Maps to `ErrorKind::WriteZero`.
+    Full,
+    /// The ring buffer doesn't contain enough bytes, or bits when reading bits.
+    /// Maps to `ErrorKind::UnexpectedEof`.
+    UnexpectedEof,
+    /// The read position can't be moved past the write position.
+    /// Maps to `ErrorKind::InvalidInput`.
+    SeekPastWrite,
+    /// The read position can't be moved back further than the bytes that can be rewound,
+    /// see [`RingBuf::rewindable`]. Maps to `ErrorKind::InvalidInput`.
+    SeekBeforeRewindable,
+    /// The write position can't be moved past the free space,
+    /// by committing or filling more bytes than are free. Maps to `ErrorKind::InvalidInput`.
+    CommitPastFree,
+    /// The write position can't be moved back past the read position.
+    /// Maps to `ErrorKind::InvalidInput`.
+    RewindPastRead,
+    /// The output buffer is too small for the bytes to read.
+    /// Maps to `ErrorKind::InvalidInput`.
+    OutputTooSmall,
+    /// The range extends past the contents of the ring buffer.
+    /// Maps to `ErrorKind::InvalidInput`.
+    RangeOutOfBounds,
+    /// A position isn't within the underlying buffer.
+    /// Maps to `ErrorKind::InvalidInput`.
+    PositionOutOfBounds,
+    /// A length-prefixed frame is larger than the capacity, so it can never be read.
+    /// Maps to `ErrorKind::InvalidData`.
+    FrameTooLarge
+}
+
+impl RingError {
+    /// Returns the `ErrorKind` this error is reported as when converted to an `io::Error`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// use bytebufrs::RingError;
+    ///
+    /// assert_eq!(RingError::SeekPastWrite.kind(), ErrorKind::InvalidInput);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            RingError::Overflow | RingError::Full => ErrorKind::WriteZero,
+            RingError::UnexpectedEof => ErrorKind::UnexpectedEof,
+            RingError::SeekPastWrite
+            | RingError::SeekBeforeRewindable
+            | RingError::CommitPastFree
+            | RingError::RewindPastRead
+            | RingError::OutputTooSmall
+            | RingError::RangeOutOfBounds
+            | RingError::PositionOutOfBounds => ErrorKind::InvalidInput,
+            RingError::FrameTooLarge => ErrorKind::InvalidData
+        }
+    }
+}
+
+impl fmt::Display for RingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RingError::Overflow => "Data is larger than the capacity.",
+            RingError::Full => "Not enough free space.",
+            RingError::UnexpectedEof => "Not enough data in the ring buffer.",
+            RingError::SeekPastWrite => "Can't seek past write pos.",
+            RingError::SeekBeforeRewindable => "Can't seek back past overwritten bytes.",
+            RingError::CommitPastFree => "Can't write more bytes than are free.",
+            RingError::RewindPastRead => "Can't rewind write pos past read pos.",
+            RingError::OutputTooSmall => "Output buffer is too small.",
+            RingError::RangeOutOfBounds => "Range out of bounds.",
+            RingError::PositionOutOfBounds => "Position out of bounds.",
+            RingError::FrameTooLarge => "Frame is larger than the capacity."
+        })
+    }
+}
+
+impl std::error::Error for RingError {}
+
+impl From<RingError> for Error {
+    fn from(err: RingError) -> Self {
+        Error::new(err.kind(), err)
+    }
 }
 
 impl RingBuf {
     /// Constructs a new, empty `RingBuf` with the specified capacity.
-    /// The underlying buffer will be of length `capacity + 1`;
+    /// The underlying buffer will be of length `capacity + 1`,
+    /// see [`RingBuf::backing_len`].
     ///
     /// # Examples
     ///
@@ -60,419 +271,7726 @@ impl RingBuf {
     /// assert!(rb.is_empty());
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
-        vec!(0; capacity + 1).into_boxed_slice().into()
+        vec!(0; Self::backing_len(capacity)).into_boxed_slice().into()
     }
 
-    /// Returns the number of bytes the ring buffer can hold.
+    /// Constructs a new, empty `RingBuf` with the specified capacity
+    /// and the given [`FullPolicy`].
     ///
-    //// # Examples
+    /// # Examples
     ///
     /// ```
-    /// use bytebufrs::RingBuf;
+    /// use std::io::Write;
     ///
-    /// let rb = RingBuf::with_capacity(10);
-    /// assert_eq!(rb.capacity(), 10);
+    /// use bytebufrs::{FullPolicy, RingBuf};
+    ///
+    /// let mut rb = RingBuf::with_policy(3, FullPolicy::OverwriteOldest);
+    ///
+    /// assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+    /// assert!(rb.eq_slice(&[2, 3, 4]));
     /// ```
-    pub fn capacity(&self) -> usize {
-        check_valid!(self);
-
-        self.data.len() - 1
+    pub fn with_policy(capacity: usize, policy: FullPolicy) -> Self {
+        let mut rb = Self::with_capacity(capacity);
+        rb.policy = policy;
+        rb
     }
 
-    /// Clears the ring buffer, resetting the read and write position to 0.
+    /// Constructs a new, empty `RingBuf` with at least the specified capacity,
+    /// using the allocation of `v` as the underlying buffer, e.g. to recycle buffers from a pool.
+    ///
+    /// Any contents of `v` are discarded: it's cleared and filled with zeros
+    /// to its whole capacity, so the ring buffer uses all of the allocation
+    /// and its capacity is `v.capacity() - 1` if that's larger than `capacity`.
+    /// A new allocation is only needed if `v.capacity()` is smaller than `backing_len(capacity)`.
     ///
     /// # Examples
     ///
     /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
-    ///
-    /// rb.clear();
+    /// let mut v = Vec::with_capacity(RingBuf::backing_len(4));
+    /// v.extend_from_slice(b"old");
+    /// let ptr = v.as_ptr();
     ///
-    /// assert_eq!(rb.len(), 0);
+    /// let rb = RingBuf::from_vec_reuse(v, 4);
+    /// assert_eq!(rb.capacity(), 4);
     /// assert!(rb.is_empty());
+    /// assert_eq!(rb.into_inner().as_ptr(), ptr);
     /// ```
-    pub fn clear(&mut self) {
-        check_valid!(self);
-
-        self.read_pos = 0;
-        self.write_pos = 0;
+    pub fn from_vec_reuse(mut v: Vec<u8>, capacity: usize) -> Self {
+        v.clear();
+        v.reserve_exact(Self::backing_len(capacity));
+        v.resize(v.capacity(), 0);
+        v.into_boxed_slice().into()
     }
 
-    /// Returns the number of bytes in the ring buffer, also referred to
-    /// as its 'length'.
+    /// Constructs a new, full `RingBuf` with the specified capacity
+    /// where every byte is set to `byte`.
     ///
     /// # Examples
     ///
     /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let rb: RingBuf = vec![1, 2, 3].into();
-    /// assert_eq!(rb.len(), 3);
+    /// let rb = RingBuf::filled(4, 0xFF);
+    /// assert_eq!(rb.capacity(), 4);
+    /// assert_eq!(rb.len(), 4);
+    ///
+    /// let mut buf = [0u8; 10];
+    /// assert_eq!(rb.peek(&mut buf).unwrap(), 4);
+    /// assert_eq!(buf[..4], [0xFF; 4]);
     /// ```
-    pub fn len(&self) -> usize {
-        check_valid!(self);
-
-        if self.read_pos > self.write_pos {
-            self.data.len() - self.read_pos + self.write_pos
-        }
-        else {
-            self.write_pos - self.read_pos
-        }
+    pub fn filled(capacity: usize, byte: u8) -> Self {
+        let mut rb = Self::with_capacity(capacity);
+        rb.data[..capacity].fill(byte);
+        rb.write_pos = capacity;
+        rb
     }
 
-    /// Returns `true` if the ring buffer doesn't contain any bytes.
+    /// Constructs a new `RingBuf` with the specified capacity containing the bytes of `iter`.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if `iter` yields more than `capacity` bytes.
+    /// Unlike writing with `FullPolicy::Grow`, the capacity never changes.
     ///
     /// # Examples
     ///
     /// ```
-    /// use std::io::Write;
-    ///
     /// use bytebufrs::RingBuf;
     ///
-    /// let mut rb = RingBuf::with_capacity(10);
-    /// assert!(rb.is_empty());
+    /// let rb = RingBuf::try_collect_from(0..3, 4).unwrap();
+    /// assert_eq!(rb.capacity(), 4);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
     ///
-    /// rb.write(&[0, 1, 2, 3]).unwrap();
-    /// assert!(!rb.is_empty());
+    /// assert!(RingBuf::try_collect_from(0..5, 4).is_err());
     /// ```
-    pub fn is_empty(&self) -> bool {
-        check_valid!(self);
+    pub fn try_collect_from<I: IntoIterator<Item = u8>>(iter: I, capacity: usize) -> Result<Self> {
+        let mut rb = Self::with_capacity(capacity);
+        for byte in iter {
+            if rb.write_truncating(&[byte]) == 0 {
+                return Err(RingError::Overflow.into());
+            }
+        }
 
-        self.read_pos == self.write_pos
+        Ok(rb)
     }
 
-    /// Advances the read position by count.
-    /// The read position can't go past the write position.
+    /// Creates a ring buffer with the given slice as backing buffer,
+    /// like `From<Box<[u8]>>`, but fails with `ErrorKind::InvalidInput` instead of panicking
+    /// if the slice is empty.
     ///
     /// # Examples
     ///
     /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let mut rb: RingBuf = vec![0, 1, 2].into();
-    /// assert_eq!(rb.len(), 3);
-    /// rb.advance_read_pos(3).unwrap();
-    /// assert_eq!(rb.len(), 0);
+    /// let rb = RingBuf::try_from_boxed_slice(vec![0u8; 5].into_boxed_slice()).unwrap();
+    /// assert_eq!(rb.capacity(), 4);
+    ///
+    /// assert!(RingBuf::try_from_boxed_slice(Box::new([])).is_err());
     /// ```
+    pub fn try_from_boxed_slice(s: Box<[u8]>) -> Result<Self> {
+        if s.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Backing buffer must not be empty."));
+        }
+
+        Ok(s.into())
+    }
+
+    /// Creates a ring buffer from an underlying buffer and read and write positions,
+    /// as returned by [`RingBuf::into_parts`]. The contents are the bytes from `read_pos`
+    /// up to `write_pos`, wrapping around the end of `data` if `write_pos < read_pos`.
+    /// If both positions are equal the ring buffer is empty, and they're reset to 0.
     ///
-    /// Trying to set the read position past the write position will fail:
+    /// Fails with `ErrorKind::InvalidInput` if either position isn't smaller than `data.len()`.
     ///
-    /// ```should_panic
+    /// # Examples
+    ///
+    /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let mut rb = RingBuf::with_capacity(10);
-    /// rb.advance_read_pos(1).unwrap(); // Will panic!
+    /// let rb = RingBuf::from_parts(vec![2, 0, 0, 1].into_boxed_slice(), 3, 1).unwrap();
+    /// assert_eq!(rb.capacity(), 3);
+    /// assert!(rb.eq_slice(&[1, 2]));
+    ///
+    /// assert!(RingBuf::from_parts(vec![0; 4].into_boxed_slice(), 0, 4).is_err());
     /// ```
-    pub fn advance_read_pos(&mut self, count: usize) -> Result<()> {
-        check_valid!(self);
-
-        if count > self.len() {
-            return Err(Error::new(ErrorKind::InvalidInput, "Can't seek past write pos."));
+    pub fn from_parts(data: Box<[u8]>, read_pos: usize, write_pos: usize) -> Result<Self> {
+        if read_pos >= data.len() || write_pos >= data.len() {
+            return Err(RingError::PositionOutOfBounds.into());
         }
 
-        self.read_pos += count;
-        if self.read_pos >= self.data.len() {
-            self.read_pos -= self.data.len();
-        }
+        let mut rb: RingBuf = data.into();
+        rb.read_pos = read_pos;
+        rb.write_pos = write_pos;
+        rb.reset_if_empty();
+        rb.update_high_water_mark();
+        Ok(rb)
+    }
 
-        Ok(())
+    /// Returns the length of the underlying buffer needed for a ring buffer
+    /// with the specified capacity, i.e. `capacity + 1`.
+    /// Useful for sizing a buffer that is converted with `From<Box<[u8]>>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0u8; RingBuf::backing_len(10)].into_boxed_slice().into();
+    /// assert_eq!(rb.capacity(), 10);
+    /// ```
+    pub const fn backing_len(capacity: usize) -> usize {
+        match capacity.checked_add(1) {
+            Some(len) => len,
+            None => panic!("capacity overflow")
+        }
     }
 
-    /// Reads from the ring buffer without advancing the read position.
-    /// On success,returns the number of bytes peeked.
+    /// Returns the capacity of a ring buffer backed by a buffer of length `backing_len`,
+    /// i.e. `backing_len - 1`, or 0 if `backing_len` is 0.
+    /// This is the inverse of [`RingBuf::backing_len`].
     ///
     /// # Examples
     ///
     /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let mut rb: RingBuf = vec![0, 1, 2].into();
-    /// assert_eq!(rb.len(), 3);
+    /// assert_eq!(RingBuf::capacity_of(11), 10);
+    /// assert_eq!(RingBuf::capacity_of(0), 0);
+    /// ```
+    pub const fn capacity_of(backing_len: usize) -> usize {
+        backing_len.saturating_sub(1)
+    }
+
+    /// Returns the number of bytes the ring buffer can hold.
+    ///
+    //// # Examples
     ///
-    /// let mut buf = [0u8; 10];
-    /// rb.peek(&mut buf).unwrap();
-    /// assert_eq!(buf, [0, 1, 2, 0, 0, 0, 0, 0, 0, 0]);
-    /// assert_eq!(rb.len(), 3);
     /// ```
-    pub fn peek(&self, buf: &mut [u8]) -> Result<usize> {
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(10);
+    /// assert_eq!(rb.capacity(), 10);
+    /// ```
+    pub fn capacity(&self) -> usize {
         check_valid!(self);
 
-        let to_read = min(self.len(), buf.len());
-        let bytes_until_end = self.data.len() - self.read_pos;
-        if bytes_until_end <= to_read {
-            buf[..bytes_until_end].copy_from_slice(&self.data[self.read_pos..]);
-            buf[bytes_until_end..to_read].copy_from_slice(&self.data[..to_read - bytes_until_end]);
-        }
-        else {
-            buf[..to_read].copy_from_slice(&self.data[self.read_pos..self.read_pos + to_read]);
-        }
+        self.data.len() - 1
+    }
 
-        Ok(to_read)
+    /// Returns the [`FullPolicy`] used when written data doesn't fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::{FullPolicy, RingBuf};
+    ///
+    /// let rb = RingBuf::with_capacity(10);
+    /// assert_eq!(rb.policy(), FullPolicy::Reject);
+    /// ```
+    pub fn policy(&self) -> FullPolicy {
+        self.policy
     }
-}
 
-impl From<Box<[u8]>> for RingBuf {
-    /// Creates a ring buffer with the given slice as backing buffer.
-    /// Note that the ring buffer capacity will be 1 byte less then the length of the slice.
+    /// Sets the [`FullPolicy`] used when written data doesn't fit.
     ///
     /// # Examples
     ///
     /// ```
-    /// use bytebufrs::RingBuf;
+    /// use std::io::Write;
     ///
-    /// let rb: RingBuf = vec![0u8; 5].into_boxed_slice().into();
-    /// assert_eq!(rb.capacity(), 4);
+    /// use bytebufrs::{FullPolicy, RingBuf};
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// rb.set_policy(FullPolicy::Grow);
+    ///
+    /// assert_eq!(rb.write(&[3, 4]).unwrap(), 2);
+    /// assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
     /// ```
-    fn from(s: Box<[u8]>) -> Self {
-        RingBuf {
-            data: s,
-            read_pos: 0,
-            write_pos: 0
-        }
+    pub fn set_policy(&mut self, policy: FullPolicy) {
+        self.policy = policy;
     }
-}
 
-impl From<Vec<u8>> for RingBuf {
-    /// Creates a ring buffer from the given vector.
-    /// The capacity and length of the ring buffer will be equal to the length of the vector
-    /// i.e. the ring buffer will be full.
+    /// Returns `true` if `write` panics in debug builds when it can't accept all data,
+    /// see [`RingBuf::set_panic_on_truncate`].
     ///
     /// # Examples
     ///
     /// ```
     /// use bytebufrs::RingBuf;
     ///
-    /// let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
-    /// assert_eq!(rb.capacity(), 5);
-    /// assert_eq!(rb.len(), 5);
-    /// assert!(!rb.is_empty());
+    /// let rb = RingBuf::with_capacity(3);
+    /// assert!(!rb.panic_on_truncate());
     /// ```
-    fn from(mut s: Vec<u8>) -> Self {
-        s.push(0);
-        let write_pos = s.len() - 1;
-        RingBuf {
-            data: s.into_boxed_slice(),
-            read_pos: 0,
-            write_pos
-        }
+    pub fn panic_on_truncate(&self) -> bool {
+        self.panic_on_truncate
     }
-}
-
-impl Read for RingBuf {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
-        check_valid!(self);
 
-        let bytes_read = self.peek(buf)?;
-        self.advance_read_pos(bytes_read)?;
-        Ok(bytes_read)
+    /// Sets whether `write` panics when it can't accept all data, i.e. when it does a short
+    /// write under `FullPolicy::Reject`, to catch unintended data loss during development.
+    ///
+    /// The check is a `debug_assert!`, so this has no effect in release builds.
+    /// It's disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    /// rb.set_panic_on_truncate(true);
+    ///
+    /// // Fits, so this doesn't panic.
+    /// rb.write(&[0, 1, 2]).unwrap();
+    /// ```
+    pub fn set_panic_on_truncate(&mut self, panic_on_truncate: bool) {
+        self.panic_on_truncate = panic_on_truncate;
     }
-}
 
-impl Write for RingBuf {
-    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+    /// Clears the ring buffer, resetting the read and write position to 0.
+    /// Any partially read or written bits are discarded as well.
+    /// If enabled with [`RingBuf::set_zero_on_clear`], the underlying buffer is zeroed first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    ///
+    /// rb.clear();
+    ///
+    /// assert_eq!(rb.len(), 0);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn clear(&mut self) {
         check_valid!(self);
 
-        let to_write = min(self.capacity() - self.len(), buf.len());
-        let bytes_until_end = self.data.len() - self.write_pos;
-        if bytes_until_end <= to_write {
-            self.data[self.write_pos..].copy_from_slice(&buf[..bytes_until_end]);
-            self.data[..to_write - bytes_until_end].copy_from_slice(&buf[bytes_until_end..to_write]);
-            self.write_pos = to_write - bytes_until_end;
-        }
-        else {
-            self.data[self.write_pos..self.write_pos + to_write].copy_from_slice(&buf[..to_write]);
-            self.write_pos += to_write;
+        if self.zero_on_clear {
+            self.data.fill(0);
         }
 
-        Ok(to_write)
+        let was_empty = self.is_empty();
+        self.total_read = self.total_read.wrapping_add(self.len());
+        self.read_pos = 0;
+        self.write_pos = 0;
+        self.read_bit_pos = 0;
+        self.write_bit_buf = 0;
+        self.write_bit_len = 0;
+        self.consumed = 0;
+        if !was_empty {
+            self.fire_event(BufEvent::BecameEmpty);
+        }
     }
 
-    fn flush(&mut self) -> Result<()> {
+    /// Returns `true` if the underlying buffer is zeroed when the ring buffer is cleared,
+    /// reallocated or dropped, see [`RingBuf::set_zero_on_clear`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(3);
+    /// assert!(!rb.zero_on_clear());
+    /// ```
+    pub fn zero_on_clear(&self) -> bool {
+        self.zero_on_clear
+    }
+
+    /// Sets whether the underlying buffer is overwritten with zeros when the ring buffer
+    /// is cleared, moves to a new underlying buffer because it grows or shrinks, or is dropped,
+    /// so sensitive data isn't left behind in memory. It's disabled by default.
+    ///
+    /// This is best-effort: as this crate doesn't use unsafe code, the zeroing is done
+    /// with ordinary writes, which the compiler may in principle optimize away,
+    /// and copies made elsewhere, e.g. by `swap_backing` or `into_vec`, aren't covered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(8);
+    /// rb.set_zero_on_clear(true);
+    ///
+    /// rb.write(b"secret").unwrap();
+    /// rb.clear();
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn set_zero_on_clear(&mut self, zero_on_clear: bool) {
+        self.zero_on_clear = zero_on_clear;
+    }
+
+    /// Returns `true` if both positions are reset to 0 whenever reading or discarding bytes
+    /// empties the ring buffer, see [`RingBuf::set_reset_on_empty`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(4);
+    /// assert!(!rb.reset_on_empty());
+    /// ```
+    pub fn reset_on_empty(&self) -> bool {
+        self.reset_on_empty
+    }
+
+    /// Sets whether both positions are reset to 0 whenever reading or discarding bytes
+    /// empties the ring buffer, so the next write gets all free space as a single contiguous run.
+    /// It's disabled by default.
+    ///
+    /// As this moves the read position, read bytes can't be rewound over anymore
+    /// once the ring buffer has been emptied, see [`RingBuf::rewindable`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.set_reset_on_empty(true);
+    ///
+    /// rb.write(&[0, 1, 2]).unwrap();
+    /// rb.advance_read_pos(3).unwrap();
+    /// assert_eq!((rb.read_offset(), rb.write_offset()), (0, 0));
+    /// assert_eq!(rb.rewind_read(), 0);
+    /// ```
+    pub fn set_reset_on_empty(&mut self, reset_on_empty: bool) {
+        self.reset_on_empty = reset_on_empty;
+    }
+
+    /// Returns the factor the capacity is multiplied by, at least, when `reserve` or
+    /// writing with `FullPolicy::Grow` grows the ring buffer. Defaults to 2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(4);
+    /// assert_eq!(rb.growth_factor(), 2.0);
+    /// ```
+    pub fn growth_factor(&self) -> f32 {
+        self.growth_factor
+    }
+
+    /// Sets the factor the capacity is multiplied by, at least, when `reserve` or
+    /// writing with `FullPolicy::Grow` grows the ring buffer.
+    /// The new capacity is the larger of the needed capacity and
+    /// `capacity() * factor`, rounded up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` isn't greater than 1 or isn't finite, e.g. `f32::INFINITY` or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.set_growth_factor(1.5);
+    ///
+    /// rb.reserve(5);
+    /// assert_eq!(rb.capacity(), 6);
+    /// ```
+    pub fn set_growth_factor(&mut self, factor: f32) {
+        assert!(factor > 1.0 && factor.is_finite(), "growth factor must be greater than 1 and finite");
+
+        self.growth_factor = factor;
+    }
+
+    /// Returns the fraction of the capacity below which [`RingBuf::compacting_read`]
+    /// moves the remaining contents to the start of the underlying buffer. Defaults to 0.25.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(4);
+    /// assert_eq!(rb.compact_threshold(), 0.25);
+    /// ```
+    pub fn compact_threshold(&self) -> f32 {
+        self.compact_threshold
+    }
+
+    /// Sets the fraction of the capacity below which [`RingBuf::compacting_read`]
+    /// moves the remaining contents to the start of the underlying buffer.
+    /// 0 disables compacting, 1 compacts whenever the ring buffer isn't full after the read.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` isn't between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.set_compact_threshold(0.5);
+    /// assert_eq!(rb.compact_threshold(), 0.5);
+    /// ```
+    pub fn set_compact_threshold(&mut self, threshold: f32) {
+        assert!((0.0..=1.0).contains(&threshold), "compact threshold must be between 0 and 1");
+
+        self.compact_threshold = threshold;
+    }
+
+    /// Sets a handler that's called when writing fills the ring buffer
+    /// or reading, discarding or clearing bytes empties it, replacing any previous handler.
+    /// This covers every method that writes or consumes bytes, including `write!`.
+    ///
+    /// The handler is only called when the state changes, not on every call,
+    /// so writing to a ring buffer that was already full doesn't report `BufEvent::BecameFull` again,
+    /// not even when `FullPolicy::OverwriteOldest` drops bytes to make room.
+    /// The handler has to be `Send` and `Sync` so the ring buffer stays `Send` and `Sync`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Read, Write};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// use bytebufrs::{BufEvent, RingBuf};
+    ///
+    /// let events = Arc::new(Mutex::new(Vec::new()));
+    /// let handler_events = Arc::clone(&events);
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.set_event_handler(Box::new(move |event| handler_events.lock().unwrap().push(event)));
+    ///
+    /// rb.write_all(&[0, 1, 2, 3]).unwrap();
+    /// rb.read_exact(&mut [0u8; 4]).unwrap();
+    /// assert_eq!(*events.lock().unwrap(), [BufEvent::BecameFull, BufEvent::BecameEmpty]);
+    /// ```
+    pub fn set_event_handler(&mut self, f: Box<dyn FnMut(BufEvent) + Send + Sync>) {
+        self.event_handler = Some(f);
+    }
+
+    /// Clears the ring buffer and changes its capacity to `new_capacity`,
+    /// discarding the contents.
+    /// The existing underlying buffer is reused if the capacity doesn't change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    ///
+    /// rb.clear_and_resize(10);
+    ///
+    /// assert_eq!(rb.capacity(), 10);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn clear_and_resize(&mut self, new_capacity: usize) {
+        check_valid!(self);
+
+        self.clear();
+
+        if new_capacity != self.capacity() {
+            self.data = vec![0; Self::backing_len(new_capacity)].into_boxed_slice();
+        }
+    }
+
+    /// Replaces the contents of `dest` with a copy of the contents of this ring buffer,
+    /// leaving the read position of `dest` at the start of its underlying buffer.
+    ///
+    /// The underlying buffer of `dest` is reused if its capacity is at least `len()`,
+    /// otherwise it's replaced by one with the capacity of this ring buffer.
+    /// The [`FullPolicy`] of `dest` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut dest = RingBuf::with_capacity(8);
+    ///
+    /// rb.clone_into(&mut dest);
+    /// assert!(dest.eq_slice(&[0, 1, 2]));
+    /// assert_eq!(dest.capacity(), 8);
+    /// ```
+    pub fn clone_into(&self, dest: &mut RingBuf) {
+        check_valid!(self);
+
+        if dest.capacity() < self.len() {
+            dest.clear_and_resize(self.capacity());
+        }
+        else {
+            dest.clear();
+        }
+
+        let (first, second) = self.as_slices();
+        dest.write_truncating(first);
+        dest.write_truncating(second);
+    }
+
+    /// Captures the capacity, read and write positions and contents of the ring buffer,
+    /// so they can be reinstated later with [`RingBuf::restore`].
+    ///
+    /// Only the contents are stored, not the whole underlying buffer.
+    /// Settings like the [`FullPolicy`] aren't captured.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1]).unwrap();
+    /// let snapshot = rb.snapshot();
+    ///
+    /// rb.write(&[2, 3]).unwrap();
+    /// rb.restore(&snapshot);
+    /// assert!(rb.eq_slice(&[0, 1]));
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        check_valid!(self);
+
+        Snapshot {
+            capacity: self.capacity(),
+            read_pos: self.read_pos,
+            bytes: self.to_vec()
+        }
+    }
+
+    /// Reinstates the state captured by [`RingBuf::snapshot`], replacing the underlying buffer
+    /// if its capacity differs. The contents end up at the same positions of the underlying
+    /// buffer, so the result is `structural_eq` to the ring buffer the snapshot was taken of.
+    ///
+    /// Any partially read or written bits are discarded and, like after [`RingBuf::clear`],
+    /// nothing can be rewound.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut other = RingBuf::with_capacity(10);
+    ///
+    /// other.restore(&rb.snapshot());
+    /// assert!(other.structural_eq(&rb));
+    /// ```
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        check_valid!(self);
+
+        self.clear_and_resize(snapshot.capacity);
+        self.read_pos = snapshot.read_pos;
+        self.write_pos = snapshot.read_pos;
+        self.write_truncating(&snapshot.bytes);
+    }
+
+    /// Reserves capacity for at least `additional` more bytes than `len()`.
+    /// The capacity may grow more than needed, at least by the growth factor,
+    /// see [`RingBuf::set_growth_factor`], to avoid frequent reallocations.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// When growing, the contents are moved to the start of the new underlying buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// rb.reserve(1);
+    /// assert_eq!(rb.capacity(), 6);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        check_valid!(self);
+
+        let needed = self.len().checked_add(additional).expect("capacity overflow");
+        self.grow(needed);
+    }
+
+    /// Reserves capacity for exactly `additional` more bytes than `len()`.
+    /// Does nothing if the capacity is already sufficient.
+    ///
+    /// When growing, the contents are moved to the start of the new underlying buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// rb.reserve_exact(1);
+    /// assert_eq!(rb.capacity(), 4);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn reserve_exact(&mut self, additional: usize) {
+        check_valid!(self);
+
+        let needed = self.len().checked_add(additional).expect("capacity overflow");
+        if needed > self.capacity() {
+            self.reallocate(needed);
+        }
+    }
+
+    /// Shrinks the capacity of the ring buffer to exactly `len()`.
+    /// Does nothing if the ring buffer is already full.
+    ///
+    /// The contents are moved to the start of the new underlying buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// rb.write(&[0, 1, 2]).unwrap();
+    ///
+    /// rb.shrink_to_fit();
+    /// assert_eq!(rb.capacity(), 3);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        check_valid!(self);
+
+        if self.len() < self.capacity() {
+            self.reallocate(self.len());
+        }
+    }
+
+    /// Returns the number of bytes in the ring buffer, also referred to
+    /// as its 'length'.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![1, 2, 3].into();
+    /// assert_eq!(rb.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        check_valid!(self);
+
+        if self.read_pos > self.write_pos {
+            self.data.len() - self.read_pos + self.write_pos
+        }
+        else {
+            self.write_pos - self.read_pos
+        }
+    }
+
+    /// Returns the highest length the ring buffer has reached since it was constructed
+    /// or [`RingBuf::reset_high_water_mark`] was last called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// rb.advance_read_pos(3).unwrap();
+    ///
+    /// assert_eq!(rb.len(), 1);
+    /// assert_eq!(rb.high_water_mark(), 4);
+    /// ```
+    pub fn high_water_mark(&self) -> usize {
         check_valid!(self);
 
-        Ok(())
+        self.high_water_mark.max(self.len())
+    }
+
+    /// Resets the high water mark to the current length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3].into();
+    /// rb.advance_read_pos(3).unwrap();
+    ///
+    /// rb.reset_high_water_mark();
+    /// assert_eq!(rb.high_water_mark(), 1);
+    /// ```
+    pub fn reset_high_water_mark(&mut self) {
+        check_valid!(self);
+
+        self.high_water_mark = self.len();
+    }
+
+    /// Returns how full the ring buffer is, as the fraction `len() / capacity()`.
+    /// Returns 0 if the capacity is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0]).unwrap();
+    /// assert_eq!(rb.utilization(), 0.25);
+    /// ```
+    pub fn utilization(&self) -> f32 {
+        check_valid!(self);
+
+        if self.capacity() == 0 {
+            return 0.0;
+        }
+
+        self.len() as f32 / self.capacity() as f32
+    }
+
+    /// Returns `true` if the ring buffer doesn't contain any bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// assert!(rb.is_empty());
+    ///
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// assert!(!rb.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        check_valid!(self);
+
+        self.read_pos == self.write_pos
+    }
+
+    /// Returns `true` if the ring buffer contains `capacity()` bytes,
+    /// i.e. no more bytes can be written until some are read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(2);
+    /// assert!(!rb.is_full());
+    ///
+    /// rb.write(&[0, 1]).unwrap();
+    /// assert!(rb.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        check_valid!(self);
+
+        self.remaining() == 0
+    }
+
+    /// Returns the number of bytes that can be written to the ring buffer
+    /// before it is full, i.e. `capacity() - len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// assert_eq!(rb.remaining(), 6);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        check_valid!(self);
+
+        self.capacity() - self.len()
+    }
+
+    /// Returns the index in the underlying buffer of the next byte to be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// rb.advance_read_pos(2).unwrap();
+    /// assert_eq!(rb.read_offset(), 2);
+    /// ```
+    pub fn read_offset(&self) -> usize {
+        check_valid!(self);
+
+        self.read_pos
+    }
+
+    /// Returns the index in the underlying buffer where the next byte will be written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.write_offset(), 3);
+    /// ```
+    pub fn write_offset(&self) -> usize {
+        check_valid!(self);
+
+        self.write_pos
+    }
+
+    /// Returns the logical read and write positions as a `(read, write)` pair.
+    ///
+    /// Unlike [`RingBuf::read_offset`] and [`RingBuf::write_offset`], these don't wrap around
+    /// the end of the underlying buffer: the read position is the number of bytes read
+    /// or discarded so far, and the write position is the read position plus `len()`,
+    /// so it grows by the number of bytes written. Rewinding and inserting bytes in front
+    /// move them back, while compacting or resetting the offsets doesn't change them.
+    /// They wrap around at `usize::MAX`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    /// rb.write(&[0, 1, 2]).unwrap();
+    /// rb.advance_read_pos(3).unwrap();
+    /// rb.write(&[3, 4]).unwrap();
+    /// assert_eq!(rb.logical_positions(), (3, 5));
+    /// assert_eq!((rb.read_offset(), rb.write_offset()), (3, 1));
+    /// ```
+    pub fn logical_positions(&self) -> (usize, usize) {
+        check_valid!(self);
+
+        (self.total_read, self.total_read.wrapping_add(self.len()))
+    }
+
+    /// Advances the read position by count.
+    /// The read position can't go past the write position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.len(), 3);
+    /// rb.advance_read_pos(3).unwrap();
+    /// assert_eq!(rb.len(), 0);
+    /// ```
+    ///
+    /// Trying to set the read position past the write position will fail:
+    ///
+    /// ```should_panic
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// rb.advance_read_pos(1).unwrap(); // Will panic!
+    /// ```
+    pub fn advance_read_pos(&mut self, count: usize) -> Result<()> {
+        check_valid!(self);
+
+        if count > self.len() {
+            return Err(RingError::SeekPastWrite.into());
+        }
+
+        self.consume(count);
+        Ok(())
+    }
+
+    /// Advances the read position by count, or by `len()` if count is larger,
+    /// and returns the number of bytes actually advanced.
+    /// Unlike [`RingBuf::advance_read_pos`] this never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.advance_read_pos_clamped(5), 3);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn advance_read_pos_clamped(&mut self, count: usize) -> usize {
+        check_valid!(self);
+
+        let count = min(count, self.len());
+        self.consume(count);
+        count
+    }
+
+    /// Discards everything except the newest `min(n, len())` bytes
+    /// and returns the number of bytes discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3].into();
+    /// assert_eq!(rb.keep_last(3), 1);
+    /// assert!(rb.eq_slice(&[1, 2, 3]));
+    ///
+    /// assert_eq!(rb.keep_last(5), 0);
+    /// assert_eq!(rb.len(), 3);
+    /// ```
+    pub fn keep_last(&mut self, n: usize) -> usize {
+        check_valid!(self);
+
+        let count = self.len() - min(n, self.len());
+        self.consume(count);
+        count
+    }
+
+    /// Advances the read position past the leading bytes for which `f` returns `true`,
+    /// and returns the number of bytes skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = b"  \tfoo ".to_vec().into();
+    ///
+    /// assert_eq!(rb.trim_start(|b| b.is_ascii_whitespace()), 3);
+    /// assert!(rb.eq_slice(b"foo "));
+    /// ```
+    pub fn trim_start<F: FnMut(u8) -> bool>(&mut self, mut f: F) -> usize {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        let count = first.iter().chain(second).position(|&b| !f(b))
+            .unwrap_or(first.len() + second.len());
+        self.consume(count);
+        count
+    }
+
+    /// Moves the read position back over bytes that were already read,
+    /// so they will be read again, and returns the number of bytes moved back.
+    ///
+    /// Read bytes stay in the underlying buffer until they are overwritten by later writes,
+    /// so this rewinds by the number of bytes read since the ring buffer was last cleared
+    /// or had its contents rearranged, limited to those that haven't been overwritten,
+    /// see [`RingBuf::rewindable`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3].into();
+    ///
+    /// let mut buf = [0u8; 3];
+    /// rb.read(&mut buf).unwrap();
+    /// assert_eq!(rb.len(), 1);
+    ///
+    /// assert_eq!(rb.rewind_read(), 3);
+    /// assert!(rb.eq_slice(&[0, 1, 2, 3]));
+    /// ```
+    pub fn rewind_read(&mut self) -> usize {
+        check_valid!(self);
+
+        let count = self.rewindable();
+        self.unconsume(count);
+        count
+    }
+
+    /// Moves the read position forward by `delta` bytes if it's positive,
+    /// or back over already read bytes by `-delta` bytes if it's negative.
+    ///
+    /// The valid range is `-(rewindable() as isize)..=len() as isize`: moving forward is limited
+    /// to the buffered bytes, and moving back to the read bytes that haven't been overwritten
+    /// by later writes, see [`RingBuf::rewindable`].
+    /// Fails with `ErrorKind::InvalidInput` if `delta` is out of range,
+    /// in which case the read position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3].into();
+    ///
+    /// rb.seek_read_relative(3).unwrap();
+    /// assert!(rb.eq_slice(&[3]));
+    ///
+    /// rb.seek_read_relative(-2).unwrap();
+    /// assert!(rb.eq_slice(&[1, 2, 3]));
+    ///
+    /// assert!(rb.seek_read_relative(-2).is_err());
+    /// assert!(rb.seek_read_relative(4).is_err());
+    /// ```
+    pub fn seek_read_relative(&mut self, delta: isize) -> Result<()> {
+        check_valid!(self);
+
+        let count = delta.unsigned_abs();
+        if delta >= 0 {
+            return self.advance_read_pos(count);
+        }
+
+        if count > self.rewindable() {
+            return Err(RingError::SeekBeforeRewindable.into());
+        }
+
+        self.unconsume(count);
+        Ok(())
+    }
+
+    /// Returns the number of already read bytes that [`RingBuf::rewind_read`] would move back over.
+    ///
+    /// This is the number of bytes read since the ring buffer was last cleared or had its contents
+    /// rearranged (by `insert_front`, `swap_backing`, growing or, if enabled with
+    /// [`RingBuf::set_reset_on_empty`], emptying), limited to `remaining()`,
+    /// as every byte written since then overwrites the oldest read byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3].into();
+    ///
+    /// rb.advance_read_pos(3).unwrap();
+    /// assert_eq!(rb.rewindable(), 3);
+    ///
+    /// rb.write(&[4]).unwrap();
+    /// assert_eq!(rb.rewindable(), 2);
+    /// ```
+    pub fn rewindable(&self) -> usize {
+        check_valid!(self);
+
+        min(self.consumed, self.remaining())
+    }
+
+    /// Reads from the ring buffer without advancing the read position.
+    /// On success,returns the number of bytes peeked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.len(), 3);
+    ///
+    /// let mut buf = [0u8; 10];
+    /// rb.peek(&mut buf).unwrap();
+    /// assert_eq!(buf, [0, 1, 2, 0, 0, 0, 0, 0, 0, 0]);
+    /// assert_eq!(rb.len(), 3);
+    /// ```
+    pub fn peek(&self, buf: &mut [u8]) -> Result<usize> {
+        check_valid!(self);
+
+        let to_read = min(self.len(), buf.len());
+        let bytes_until_end = self.data.len() - self.read_pos;
+        if bytes_until_end <= to_read {
+            buf[..bytes_until_end].copy_from_slice(&self.data[self.read_pos..]);
+            buf[bytes_until_end..to_read].copy_from_slice(&self.data[..to_read - bytes_until_end]);
+        }
+        else {
+            buf[..to_read].copy_from_slice(&self.data[self.read_pos..self.read_pos + to_read]);
+        }
+
+        Ok(to_read)
+    }
+
+    /// Reads exactly `buf.len()` bytes from the ring buffer without advancing the read position.
+    ///
+    /// Fails with `ErrorKind::UnexpectedEof` if the ring buffer contains fewer bytes,
+    /// in which case `buf` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut buf = [0u8; 2];
+    /// rb.peek_exact(&mut buf).unwrap();
+    /// assert_eq!(buf, [0, 1]);
+    ///
+    /// assert!(rb.peek_exact(&mut [0u8; 4]).is_err());
+    /// ```
+    pub fn peek_exact(&self, buf: &mut [u8]) -> Result<()> {
+        check_valid!(self);
+
+        if buf.len() > self.len() {
+            return Err(RingError::UnexpectedEof.into());
+        }
+
+        self.copy_at(0, buf);
+        Ok(())
+    }
+
+    /// Reads exactly `n` bytes from the ring buffer into the start of `out`, or nothing at all.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `out` is shorter than `n`,
+    /// and with `ErrorKind::UnexpectedEof` if the ring buffer contains fewer than `n` bytes,
+    /// in both cases without consuming anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut out = [0u8; 4];
+    /// rb.drain_exact(2, &mut out).unwrap();
+    /// assert_eq!(out[..2], [0, 1]);
+    ///
+    /// assert!(rb.drain_exact(2, &mut out).is_err());
+    /// assert!(rb.eq_slice(&[2]));
+    /// ```
+    pub fn drain_exact(&mut self, n: usize, out: &mut [u8]) -> Result<()> {
+        check_valid!(self);
+
+        if out.len() < n {
+            return Err(RingError::OutputTooSmall.into());
+        }
+
+        self.peek_exact(&mut out[..n])?;
+        self.consume(n);
+        Ok(())
+    }
+
+    /// Copies the bytes at logical offsets `[start, end)` into `out` without advancing
+    /// the read position. If `out` is shorter than the range, only the first `out.len()`
+    /// bytes of the range are copied. On success, returns the number of bytes copied.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `start > end` or `end > len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    ///
+    /// let mut out = [0u8; 5];
+    /// assert_eq!(rb.copy_range(1, 4, &mut out).unwrap(), 3);
+    /// assert_eq!(out, [1, 2, 3, 0, 0]);
+    ///
+    /// assert!(rb.copy_range(4, 6, &mut out).is_err());
+    /// ```
+    pub fn copy_range(&self, start: usize, end: usize, out: &mut [u8]) -> Result<usize> {
+        check_valid!(self);
+
+        if start > end || end > self.len() {
+            return Err(RingError::RangeOutOfBounds.into());
+        }
+
+        let count = min(end - start, out.len());
+        self.copy_at(start, &mut out[..count]);
+        Ok(count)
+    }
+
+    /// Overwrites the bytes at logical offsets `[start, start + len)` with zeros in place,
+    /// without consuming or moving any bytes, e.g. to scrub a secret that was already parsed.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if the range extends past `len()`,
+    /// in which case nothing is changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = b"user:secret;".to_vec().into();
+    ///
+    /// rb.zero_range(5, 6).unwrap();
+    /// assert!(rb.eq_slice(b"user:\0\0\0\0\0\0;"));
+    /// assert!(rb.zero_range(5, 8).is_err());
+    /// ```
+    pub fn zero_range(&mut self, start: usize, len: usize) -> Result<()> {
+        check_valid!(self);
+
+        let end = match start.checked_add(len) {
+            Some(end) if end <= self.len() => end,
+            _ => return Err(RingError::RangeOutOfBounds.into())
+        };
+
+        let (first, second) = self.as_mut_slices();
+        let split = first.len();
+        first[min(start, split)..min(end, split)].fill(0);
+        second[start.saturating_sub(split)..end.saturating_sub(split)].fill(0);
+        Ok(())
+    }
+
+    /// Reads the most recently written bytes without advancing the read position.
+    /// Copies the last `min(len(), buf.len())` bytes into `buf`, in read order,
+    /// and returns the number of bytes copied.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    ///
+    /// let mut buf = [0u8; 2];
+    /// assert_eq!(rb.peek_back(&mut buf), 2);
+    /// assert_eq!(buf, [3, 4]);
+    /// assert_eq!(rb.len(), 5);
+    /// ```
+    pub fn peek_back(&self, buf: &mut [u8]) -> usize {
+        check_valid!(self);
+
+        let len = self.len();
+        let to_read = min(len, buf.len());
+        self.copy_at(len - to_read, &mut buf[..to_read]);
+        to_read
+    }
+
+    /// Inserts `data` in front of the read position, so that it will be the next bytes read.
+    ///
+    /// Fails if the ring buffer doesn't have enough free space to hold `data`,
+    /// in which case the ring buffer is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(5);
+    /// rb.insert_front(&[2, 3]).unwrap();
+    /// rb.insert_front(&[0, 1]).unwrap();
+    ///
+    /// let mut buf = [0u8; 4];
+    /// rb.read(&mut buf).unwrap();
+    /// assert_eq!(buf, [0, 1, 2, 3]);
+    /// ```
+    pub fn insert_front(&mut self, data: &[u8]) -> Result<()> {
+        check_valid!(self);
+
+        if data.len() > self.capacity() - self.len() {
+            return Err(RingError::Full.into());
+        }
+
+        self.read_pos = if data.len() > self.read_pos {
+            self.data.len() + self.read_pos - data.len()
+        }
+        else {
+            self.read_pos - data.len()
+        };
+        self.copy_in(self.read_pos, data);
+        self.total_read = self.total_read.wrapping_sub(data.len());
+        if !data.is_empty() {
+            self.read_bit_pos = 0;
+        }
+        self.consumed = 0;
+        self.update_high_water_mark();
+
+        Ok(())
+    }
+
+    /// Writes at most `max` bytes from the ring buffer to `w`, advancing the read position
+    /// by the number of bytes `w` accepted.
+    /// On success, returns the number of bytes written to `w`.
+    ///
+    /// As the contents may wrap around the end of the underlying buffer, `w` can receive
+    /// up to two `write` calls. If the second call fails, the bytes accepted by the first call
+    /// are still consumed and their count is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    /// let mut sink = Vec::new();
+    ///
+    /// assert_eq!(rb.drain_at_most_to(&mut sink, 3).unwrap(), 3);
+    /// assert_eq!(sink, [0, 1, 2]);
+    /// assert_eq!(rb.len(), 2);
+    /// ```
+    pub fn drain_at_most_to<W: Write>(&mut self, w: &mut W, max: usize) -> Result<usize> {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        let first = &first[..min(first.len(), max)];
+        let second = &second[..min(second.len(), max - first.len())];
+
+        let mut written = w.write(first)?;
+        if written == first.len() && !second.is_empty() {
+            match w.write(second) {
+                Ok(n) => written += n,
+                // `w` already accepted the bytes of the first call, so they have to be consumed
+                // and reported; `w` is likely to return the error again on the next call.
+                Err(_) if written > 0 => {},
+                Err(err) => return Err(err)
+            }
+        }
+
+        self.advance_read_pos(written)?;
+        Ok(written)
+    }
+
+    /// Reads from the ring buffer into a possibly uninitialized buffer,
+    /// avoiding the cost of zeroing large read buffers first.
+    /// Returns the number of bytes read, which is `min(buf.len(), len())`;
+    /// that many elements at the start of `buf` are initialized.
+    ///
+    /// This takes a `MaybeUninit` slice instead of a `std::io::BorrowedBuf`, as `BorrowedBuf`
+    /// is still unstable (`core_io_borrowed_buf`). Once it's stable, the unfilled part
+    /// of a `BorrowedCursor` can be passed here and advanced by the returned count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::mem::MaybeUninit;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut buf = [MaybeUninit::uninit(); 2];
+    ///
+    /// let n = rb.read_into_uninit(&mut buf);
+    /// assert_eq!(n, 2);
+    /// assert!(rb.eq_slice(&[2]));
+    ///
+    /// // SAFETY: `read_into_uninit` initialized the first `n` elements.
+    /// let read: Vec<u8> = buf[..n].iter().map(|b| unsafe { b.assume_init() }).collect();
+    /// assert_eq!(read, [0, 1]);
+    /// ```
+    pub fn read_into_uninit(&mut self, buf: &mut [MaybeUninit<u8>]) -> usize {
+        check_valid!(self);
+
+        let count = min(buf.len(), self.len());
+        let (first, second) = self.as_slices();
+        let head = min(first.len(), count);
+        let src = first[..head].iter().chain(&second[..count - head]);
+        for (dst, &byte) in buf.iter_mut().zip(src) {
+            dst.write(byte);
+        }
+
+        self.consume(count);
+        count
+    }
+
+    /// Reads all bytes in the ring buffer and appends them to `out`.
+    /// Returns the number of bytes read.
+    ///
+    /// Unlike `Read::read_to_end`, this copies the contents with at most two
+    /// `extend_from_slice` calls instead of looping over `read`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut out = vec![9];
+    ///
+    /// assert_eq!(rb.read_to_end_ring(&mut out), 3);
+    /// assert_eq!(out, [9, 0, 1, 2]);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn read_to_end_ring(&mut self, out: &mut Vec<u8>) -> usize {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        let count = first.len() + second.len();
+        out.reserve(count);
+        out.extend_from_slice(first);
+        out.extend_from_slice(second);
+
+        self.consume(count);
+        count
+    }
+
+    /// Reads all bytes currently in the ring buffer and appends them to `out` as text,
+    /// returning the number of bytes read.
+    ///
+    /// Unlike `Read::read_to_string`, this doesn't read until `read` returns `Ok(0)`,
+    /// which for a ring buffer only means it's empty right now, and the bytes are validated as
+    /// a whole, so a character split by the end of the underlying buffer is fine.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the bytes aren't valid UTF-8,
+    /// in which case nothing is read and `out` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = "grüß".as_bytes().to_vec().into();
+    /// let mut out = String::from("> ");
+    ///
+    /// assert_eq!(rb.read_to_string_ring(&mut out).unwrap(), 6);
+    /// assert_eq!(out, "> grüß");
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn read_to_string_ring(&mut self, out: &mut String) -> Result<usize> {
+        check_valid!(self);
+
+        let count = {
+            let bytes = self.peek_cow();
+            let text = std::str::from_utf8(&bytes)
+                .map_err(|_| Error::new(ErrorKind::InvalidData, "Stream did not contain valid UTF-8."))?;
+            out.push_str(text);
+            bytes.len()
+        };
+
+        self.consume(count);
+        Ok(count)
+    }
+
+    /// Reads all bytes up to and including the next newline (`0xA` byte)
+    /// and appends them to `out`.
+    /// If the ring buffer doesn't contain a newline, all remaining bytes are read.
+    /// On success, returns the number of bytes read.
+    ///
+    /// Fails with `ErrorKind::InvalidData` if the bytes aren't valid UTF-8,
+    /// in which case nothing is read and `out` is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = b"foo\nbar".to_vec().into();
+    /// let mut line = String::new();
+    ///
+    /// assert_eq!(rb.read_line(&mut line).unwrap(), 4);
+    /// assert_eq!(line, "foo\n");
+    ///
+    /// assert_eq!(rb.read_line(&mut line).unwrap(), 3);
+    /// assert_eq!(line, "foo\nbar");
+    /// ```
+    pub fn read_line(&mut self, out: &mut String) -> Result<usize> {
+        check_valid!(self);
+
+        let count = self.find_byte(b'\n', 0).map_or(self.len(), |i| i + 1);
+        let mut bytes = vec![0; count];
+        self.copy_at(0, &mut bytes);
+        let line = String::from_utf8(bytes)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Stream did not contain valid UTF-8."))?;
+
+        out.push_str(&line);
+        self.advance_read_pos(count)?;
+        Ok(count)
+    }
+
+    /// Reads exactly `n` bytes from the ring buffer and returns them as a `String`.
+    ///
+    /// Fails with `ErrorKind::UnexpectedEof` if the ring buffer contains fewer than `n` bytes
+    /// and with `ErrorKind::InvalidData` if the bytes aren't valid UTF-8.
+    /// In both cases nothing is read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = b"foobar".to_vec().into();
+    ///
+    /// assert_eq!(rb.read_string(3).unwrap(), "foo");
+    /// assert_eq!(rb.read_string(4).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    /// assert_eq!(rb.read_string(3).unwrap(), "bar");
+    /// ```
+    pub fn read_string(&mut self, n: usize) -> Result<String> {
+        check_valid!(self);
+
+        if n > self.len() {
+            return Err(RingError::UnexpectedEof.into());
+        }
+
+        let mut bytes = vec![0; n];
+        self.copy_at(0, &mut bytes);
+        let string = String::from_utf8(bytes)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Stream did not contain valid UTF-8."))?;
+
+        self.consume(n);
+        Ok(string)
+    }
+
+    /// Reads a frame consisting of a `prefix_bytes` wide length prefix followed by that
+    /// many bytes of body, and returns the body.
+    ///
+    /// The length prefix is an unsigned integer in big endian byte order if `big_endian` is `true`,
+    /// little endian otherwise. If the ring buffer doesn't contain the complete frame yet,
+    /// this returns `Ok(None)` without consuming anything, so it can be called again
+    /// once more bytes have been written.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `prefix_bytes` isn't in `1..=8`,
+    /// and with `ErrorKind::InvalidData` if the frame could never fit in the ring buffer,
+    /// in both cases without consuming anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(16);
+    ///
+    /// rb.write(&[0, 3, b'a', b'b']).unwrap();
+    /// assert_eq!(rb.try_read_frame(2, true).unwrap(), None);
+    ///
+    /// rb.write(&[b'c']).unwrap();
+    /// assert_eq!(rb.try_read_frame(2, true).unwrap(), Some(b"abc".to_vec()));
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn try_read_frame(&mut self, prefix_bytes: usize, big_endian: bool) -> Result<Option<Vec<u8>>> {
+        check_valid!(self);
+
+        if prefix_bytes == 0 || prefix_bytes > 8 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Length prefix must be 1 to 8 bytes."));
+        }
+
+        if self.len() < prefix_bytes {
+            return Ok(None);
+        }
+
+        let mut prefix = [0u8; 8];
+        self.copy_at(0, &mut prefix[..prefix_bytes]);
+        let prefix = &mut prefix[..prefix_bytes];
+        if !big_endian {
+            prefix.reverse();
+        }
+
+        let body_len = prefix.iter().fold(0u64, |len, &b| len << 8 | u64::from(b));
+        let total = match usize::try_from(body_len).ok().and_then(|len| len.checked_add(prefix_bytes)) {
+            Some(total) if total <= self.capacity() => total,
+            _ => return Err(RingError::FrameTooLarge.into())
+        };
+
+        if self.len() < total {
+            return Ok(None);
+        }
+
+        let mut body = vec![0; total - prefix_bytes];
+        self.copy_at(prefix_bytes, &mut body);
+        self.consume(total);
+        Ok(Some(body))
+    }
+
+    /// Reads `count` bits from the ring buffer, most significant bit first,
+    /// and returns them in the low bits of the result.
+    ///
+    /// Bits are read from the byte at the read position, which is only consumed
+    /// once all of its bits have been read. Byte-level reads and peeks start at that byte,
+    /// and any byte-level consumption discards its remaining bits,
+    /// see also [`RingBuf::byte_align`].
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `count` is larger than 64,
+    /// and with `ErrorKind::UnexpectedEof` if the ring buffer doesn't contain `count` more bits,
+    /// in both cases without reading anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0b1011_0011, 0b1000_0000].into();
+    ///
+    /// assert_eq!(rb.read_bits(3).unwrap(), 0b101);
+    /// assert_eq!(rb.read_bits(6).unwrap(), 0b10_0111);
+    /// assert_eq!(rb.len(), 1);
+    /// ```
+    pub fn read_bits(&mut self, count: u8) -> Result<u64> {
+        check_valid!(self);
+
+        if count > 64 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Can't read more than 64 bits at once."));
+        }
+
+        if count as usize + self.read_bit_pos as usize > self.len() * 8 {
+            return Err(RingError::UnexpectedEof.into());
+        }
+
+        let mut value = 0u64;
+        let mut left = count;
+        while left > 0 {
+            let available = 8 - self.read_bit_pos;
+            let take = min(available, left);
+            let bits = (self.data[self.read_pos] << self.read_bit_pos) >> (8 - take);
+            value = (value << take) | bits as u64;
+            left -= take;
+
+            if take == available {
+                self.advance_read_pos(1)?;
+            }
+            else {
+                self.read_bit_pos += take;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Writes the low `count` bits of `value` to the ring buffer, most significant bit first.
+    ///
+    /// Bits are collected until they form a whole byte, which is then written to the ring buffer.
+    /// Use [`RingBuf::flush_bits`] to write out a trailing partial byte.
+    /// Byte-level writes don't take pending bits into account,
+    /// so flush them first when mixing bit-level and byte-level writes.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `count` is larger than 64,
+    /// and with `ErrorKind::WriteZero` if the completed bytes wouldn't fit,
+    /// in both cases without writing anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    ///
+    /// rb.write_bits(0b101, 3).unwrap();
+    /// rb.write_bits(0b1_0011, 5).unwrap();
+    /// assert!(rb.eq_slice(&[0b1011_0011]));
+    /// ```
+    pub fn write_bits(&mut self, value: u64, count: u8) -> Result<()> {
+        check_valid!(self);
+
+        if count > 64 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Can't write more than 64 bits at once."));
+        }
+
+        if (self.write_bit_len as usize + count as usize) / 8 > self.remaining() {
+            return Err(RingError::Full.into());
+        }
+
+        for i in (0..count).rev() {
+            self.write_bit_buf = (self.write_bit_buf << 1) | ((value >> i) & 1) as u8;
+            self.write_bit_len += 1;
+            if self.write_bit_len == 8 {
+                self.write_truncating(&[self.write_bit_buf]);
+                self.write_bit_buf = 0;
+                self.write_bit_len = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes any pending bits from [`RingBuf::write_bits`] as a byte, padded with zero bits.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if the ring buffer is full,
+    /// in which case the pending bits are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    ///
+    /// rb.write_bits(0b101, 3).unwrap();
+    /// assert!(rb.is_empty());
+    ///
+    /// rb.flush_bits().unwrap();
+    /// assert!(rb.eq_slice(&[0b1010_0000]));
+    /// ```
+    pub fn flush_bits(&mut self) -> Result<()> {
+        check_valid!(self);
+
+        if self.write_bit_len == 0 {
+            return Ok(());
+        }
+
+        if self.remaining() == 0 {
+            return Err(RingError::Full.into());
+        }
+
+        self.write_truncating(&[self.write_bit_buf << (8 - self.write_bit_len)]);
+        self.write_bit_buf = 0;
+        self.write_bit_len = 0;
+        Ok(())
+    }
+
+    /// Discards the remaining bits of a partially read byte,
+    /// so the next read starts at a byte boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0b1011_0011, 0b1000_0000].into();
+    ///
+    /// assert_eq!(rb.read_bits(3).unwrap(), 0b101);
+    /// rb.byte_align();
+    /// assert_eq!(rb.read_bits(1).unwrap(), 0b1);
+    /// ```
+    pub fn byte_align(&mut self) {
+        check_valid!(self);
+
+        if self.read_bit_pos > 0 {
+            self.consume(1);
+        }
+    }
+
+    /// Reads all bytes up to and including the first occurrence of the sequence `delim`
+    /// and appends them to `out`. On success, returns the number of bytes read.
+    ///
+    /// If the ring buffer doesn't contain all of `delim`, nothing is read and `Ok(0)` is returned,
+    /// so the caller can wait for more data. Note that this differs from [`RingBuf::read_line`],
+    /// which reads all remaining bytes when there is no delimiter.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `delim` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = b"foo\r\nbar\r".to_vec().into();
+    /// let mut out = Vec::new();
+    ///
+    /// assert_eq!(rb.read_until_sequence(b"\r\n", &mut out).unwrap(), 5);
+    /// assert_eq!(out, b"foo\r\n");
+    ///
+    /// assert_eq!(rb.read_until_sequence(b"\r\n", &mut out).unwrap(), 0);
+    /// assert_eq!(rb.len(), 4);
+    /// ```
+    pub fn read_until_sequence(&mut self, delim: &[u8], out: &mut Vec<u8>) -> Result<usize> {
+        check_valid!(self);
+
+        if delim.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidInput, "Delimiter must not be empty."));
+        }
+
+        let count = match self.find_sequence(delim) {
+            Some(i) => i + delim.len(),
+            None => return Ok(0)
+        };
+
+        let start = out.len();
+        out.resize(start + count, 0);
+        self.copy_at(0, &mut out[start..]);
+        self.consume(count);
+        Ok(count)
+    }
+
+    /// Replaces the underlying buffer with `new`, returning the old one so it can be reused.
+    ///
+    /// The contents of the ring buffer are moved to the start of `new`
+    /// and the capacity becomes `new.len() - 1`.
+    /// If the contents don't fit in the new capacity, only the oldest bytes
+    /// that fit are kept, just like `write` keeps the first bytes that fit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `new` is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let old = rb.swap_backing(vec![0u8; 11].into_boxed_slice());
+    /// assert_eq!(old.len(), 4);
+    /// assert_eq!(rb.capacity(), 10);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn swap_backing(&mut self, mut new: Box<[u8]>) -> Box<[u8]> {
+        check_valid!(self);
+
+        assert!(!new.is_empty(), "backing buffer must not be empty");
+
+        let len = min(self.len(), new.len() - 1);
+        self.copy_at(0, &mut new[..len]);
+        self.read_pos = 0;
+        self.write_pos = len;
+        self.read_bit_pos = 0;
+        self.consumed = 0;
+        std::mem::replace(&mut self.data, new)
+    }
+
+    /// Moves all bytes from `other` to the end of `self`, leaving `other` empty.
+    ///
+    /// When the bytes don't fit, `self`'s [`FullPolicy`] decides what happens:
+    /// with `FullPolicy::Reject` this fails with `ErrorKind::WriteZero`
+    /// and leaves both ring buffers unchanged, with `FullPolicy::OverwriteOldest`
+    /// the oldest bytes are dropped and with `FullPolicy::Grow` `self` grows to fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::{FullPolicy, RingBuf};
+    ///
+    /// let mut rb = RingBuf::with_policy(2, FullPolicy::Grow);
+    /// let mut other: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// rb.append(&mut other).unwrap();
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// assert!(other.is_empty());
+    /// ```
+    pub fn append(&mut self, other: &mut RingBuf) -> Result<()> {
+        check_valid!(self);
+
+        if self.policy == FullPolicy::Reject && other.len() > self.remaining() {
+            return Err(RingError::Full.into());
+        }
+
+        let (first, second) = other.as_slices();
+        self.write_all(first)?;
+        self.write_all(second)?;
+        other.clear();
+        Ok(())
+    }
+
+    /// Splits the ring buffer into two at the given offset.
+    ///
+    /// Returns a new ring buffer containing the bytes at offsets `[at, len)`,
+    /// with the same capacity as `self`.
+    /// `self` is left containing the bytes `[0, at)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    /// let tail = rb.split_off(2);
+    ///
+    /// assert!(rb.eq_slice(&[0, 1]));
+    /// assert!(tail.eq_slice(&[2, 3, 4]));
+    /// assert_eq!(tail.capacity(), 5);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> RingBuf {
+        check_valid!(self);
+
+        let len = self.len();
+        assert!(at <= len, "`at` split index (is {}) should be <= len (is {})", at, len);
+
+        let mut tail = RingBuf::with_capacity(self.capacity());
+        self.copy_at(at, &mut tail.data[..len - at]);
+        tail.write_pos = len - at;
+
+        self.unwrite(len - at);
+        tail
+    }
+
+    /// Moves the write position back by `n`, discarding the `n` most recently written bytes
+    /// so their space becomes free again.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `n` is larger than `len()`,
+    /// in which case the ring buffer is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(5);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    ///
+    /// rb.rewind_write(2).unwrap();
+    /// assert!(rb.eq_slice(&[0, 1]));
+    /// assert!(rb.rewind_write(3).is_err());
+    /// ```
+    pub fn rewind_write(&mut self, n: usize) -> Result<()> {
+        check_valid!(self);
+
+        if n > self.len() {
+            return Err(RingError::RewindPastRead.into());
+        }
+
+        self.unwrite(n);
+        Ok(())
+    }
+
+    /// Returns everything currently buffered as two slices, in read order,
+    /// without advancing the read position.
+    ///
+    /// The second slice is empty unless the contents wrap around the end of the underlying buffer,
+    /// and the total length of both slices is always `len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Read, Write};
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// rb.read_exact(&mut [0u8; 2]).unwrap();
+    /// rb.write(&[4, 5]).unwrap();
+    ///
+    /// let (first, second) = rb.peek_remaining();
+    /// assert_eq!(first, [2, 3, 4]);
+    /// assert_eq!(second, [5]);
+    /// assert_eq!([first, second].concat(), [2, 3, 4, 5]);
+    /// ```
+    pub fn peek_remaining(&self) -> (&[u8], &[u8]) {
+        check_valid!(self);
+
+        self.as_slices()
+    }
+
+    /// Copies the contents of the ring buffer into a new `Vec`, in read order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.to_vec(), [0, 1, 2]);
+    /// ```
+    pub fn to_vec(&self) -> Vec<u8> {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        let mut vec = Vec::with_capacity(first.len() + second.len());
+        vec.extend_from_slice(first);
+        vec.extend_from_slice(second);
+        vec
+    }
+
+    /// Returns an iterator over the non-empty contiguous slices of the contents
+    /// of the ring buffer, in read order, without advancing the read position.
+    ///
+    /// Unlike [`RingBuf::peek_remaining`], empty slices are skipped:
+    /// this yields no slices if the ring buffer is empty, and two only if the contents wrap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut sink = Vec::new();
+    /// for chunk in rb.iter_slices() {
+    ///     sink.write_all(chunk).unwrap();
+    /// }
+    /// assert_eq!(sink, [0, 1, 2]);
+    /// ```
+    pub fn iter_slices(&self) -> impl Iterator<Item = &[u8]> {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        std::iter::once(first).chain(std::iter::once(second)).filter(|slice| !slice.is_empty())
+    }
+
+    /// Returns the contents of the ring buffer as a single slice, in read order,
+    /// without advancing the read position.
+    ///
+    /// The contents are borrowed if they don't wrap around the end of the underlying buffer,
+    /// and only copied into a newly allocated `Vec` if they do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let contents = rb.peek_cow();
+    /// assert!(matches!(contents, Cow::Borrowed(_)));
+    /// assert_eq!(*contents, [0, 1, 2]);
+    /// ```
+    pub fn peek_cow(&self) -> Cow<'_, [u8]> {
+        check_valid!(self);
+
+        match self.as_slices() {
+            (first, []) => Cow::Borrowed(first),
+            (first, second) => Cow::Owned([first, second].concat())
+        }
+    }
+
+    /// Converts the ring buffer into a `Vec<u8>` containing its contents in read order.
+    ///
+    /// If the contents start at the beginning of the underlying buffer and don't wrap,
+    /// the underlying buffer is reused and truncated to `len()` bytes without copying.
+    /// Otherwise the contents are copied into a newly allocated `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Read, Write};
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// rb.read_exact(&mut [0u8; 2]).unwrap();
+    /// rb.write(&[4, 5]).unwrap();
+    ///
+    /// assert_eq!(rb.into_vec(), [2, 3, 4, 5]);
+    /// ```
+    pub fn into_vec(mut self) -> Vec<u8> {
+        check_valid!(self);
+
+        if self.read_pos != 0 {
+            return self.to_vec();
+        }
+
+        let len = self.write_pos;
+        let mut vec = std::mem::take(&mut self.data).into_vec();
+        vec.truncate(len);
+        vec
+    }
+
+    /// Converts the ring buffer into its underlying buffer of `backing_len(capacity())` bytes,
+    /// regardless of the contents, e.g. to pass it to [`RingBuf::from_vec_reuse`] later.
+    /// Use [`RingBuf::into_vec`] to get the contents instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::with_capacity(4);
+    /// assert_eq!(rb.into_inner().len(), RingBuf::backing_len(4));
+    /// ```
+    pub fn into_inner(mut self) -> Vec<u8> {
+        check_valid!(self);
+
+        std::mem::take(&mut self.data).into_vec()
+    }
+
+    /// Converts the ring buffer into its underlying buffer and its read and write positions,
+    /// which [`RingBuf::from_parts`] turns back into an equal ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{Read, Write};
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    /// rb.write(&[0, 1]).unwrap();
+    /// rb.read_exact(&mut [0u8; 1]).unwrap();
+    ///
+    /// let (data, read_pos, write_pos) = rb.into_parts();
+    /// assert_eq!((data.len(), read_pos, write_pos), (4, 1, 2));
+    /// ```
+    pub fn into_parts(mut self) -> (Box<[u8]>, usize, usize) {
+        check_valid!(self);
+
+        (std::mem::take(&mut self.data), self.read_pos, self.write_pos)
+    }
+
+    /// Copies the contents of the ring buffer into `scratch` and returns them as a single slice.
+    ///
+    /// `scratch` is cleared first, so its allocation can be reused across calls
+    /// to get a contiguous view of the contents without allocating every time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut scratch = Vec::new();
+    /// assert_eq!(rb.contiguous_copy(&mut scratch), [0, 1, 2]);
+    /// ```
+    pub fn contiguous_copy<'a>(&self, scratch: &'a mut Vec<u8>) -> &'a [u8] {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        scratch.clear();
+        scratch.extend_from_slice(first);
+        scratch.extend_from_slice(second);
+        scratch
+    }
+
+    /// Returns the contents of the ring buffer as two mutable slices, in read order.
+    /// The second slice is empty unless the contents wrap around the end of the underlying buffer.
+    ///
+    /// This allows changing the bytes that will be read next in place,
+    /// without moving the read or write position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let (first, second) = rb.as_mut_slices();
+    /// first.iter_mut().chain(second).for_each(|b| *b ^= 0xFF);
+    ///
+    /// assert!(rb.eq_slice(&[0xFF, 0xFE, 0xFD]));
+    /// ```
+    pub fn as_mut_slices(&mut self) -> (&mut [u8], &mut [u8]) {
+        check_valid!(self);
+
+        if self.read_pos > self.write_pos {
+            let (second, first) = self.data.split_at_mut(self.read_pos);
+            (first, &mut second[..self.write_pos])
+        }
+        else {
+            (&mut self.data[self.read_pos..self.write_pos], &mut [])
+        }
+    }
+
+    /// Computes the CRC32 checksum of the contents of the ring buffer, in read order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"123456789".to_vec().into();
+    /// assert_eq!(rb.checksum_crc32(), 0xCBF43926);
+    /// ```
+    #[cfg(feature = "crc32fast")]
+    pub fn checksum_crc32(&self) -> u32 {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(first);
+        hasher.update(second);
+        hasher.finalize()
+    }
+
+    /// Returns the logical offset of the first occurrence of `needle`,
+    /// i.e. the number of bytes before it, or `None` if the ring buffer doesn't contain it.
+    ///
+    /// With the `memchr` feature enabled, the search uses the `memchr` crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"a,b,c".to_vec().into();
+    /// assert_eq!(rb.find(b','), Some(1));
+    /// assert_eq!(rb.find(b';'), None);
+    /// ```
+    pub fn find(&self, needle: u8) -> Option<usize> {
+        check_valid!(self);
+
+        self.find_byte(needle, 0)
+    }
+
+    /// Returns the logical offset of the last occurrence of `needle`,
+    /// or `None` if the ring buffer doesn't contain it.
+    ///
+    /// With the `memchr` feature enabled, the search uses the `memchr` crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"a,b,c".to_vec().into();
+    /// assert_eq!(rb.rfind(b','), Some(3));
+    /// assert_eq!(rb.rfind(b';'), None);
+    /// ```
+    pub fn rfind(&self, needle: u8) -> Option<usize> {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        memrchr(needle, second).map(|i| first.len() + i)
+            .or_else(|| memrchr(needle, first))
+    }
+
+    /// Writes the raw bytes of `value` to the ring buffer, or nothing at all.
+    ///
+    /// The bytes are written in native endianness, so converting to a fixed byte order
+    /// is up to the caller. Like `extend_from_slice_exact`, this fails with
+    /// `ErrorKind::WriteZero` if the value doesn't fit in the free space,
+    /// regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(8);
+    ///
+    /// rb.write_pod(&0x0102_0304u32.to_be()).unwrap();
+    /// assert!(rb.eq_slice(&[1, 2, 3, 4]));
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn write_pod<T: bytemuck::Pod>(&mut self, value: &T) -> Result<()> {
+        self.extend_from_slice_exact(bytemuck::bytes_of(value))
+    }
+
+    /// Reads a `T` from the raw bytes at the front of the ring buffer.
+    ///
+    /// The bytes are interpreted in native endianness, see [`RingBuf::write_pod`].
+    /// Fails with `ErrorKind::UnexpectedEof` if the ring buffer contains fewer than
+    /// `size_of::<T>()` bytes, in which case nothing is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![1, 2, 3, 4, 5].into();
+    ///
+    /// assert_eq!(u32::from_be(rb.read_pod::<u32>().unwrap()), 0x0102_0304);
+    /// assert!(rb.read_pod::<u32>().is_err());
+    /// assert_eq!(rb.len(), 1);
+    /// ```
+    #[cfg(feature = "bytemuck")]
+    pub fn read_pod<T: bytemuck::Pod>(&mut self) -> Result<T> {
+        check_valid!(self);
+
+        let mut value = T::zeroed();
+        self.peek_exact(bytemuck::bytes_of_mut(&mut value))?;
+        self.consume(std::mem::size_of::<T>());
+        Ok(value)
+    }
+
+    /// Returns `true` if the contents of the ring buffer are equal to `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// assert!(!rb.eq_slice(&[0, 1]));
+    /// ```
+    pub fn eq_slice(&self, other: &[u8]) -> bool {
+        check_valid!(self);
+
+        if self.len() != other.len() {
+            return false;
+        }
+
+        let (first, second) = self.as_slices();
+        first == &other[..first.len()] && second == &other[first.len()..]
+    }
+
+    /// Returns `true` if both the contents and the capacity of the ring buffers are equal.
+    ///
+    /// This is stricter than `==`, which only compares the contents,
+    /// for tests that need to assert a ring buffer was reconstructed exactly.
+    /// Where the contents are located in the underlying buffer isn't compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let a: RingBuf = vec![0, 1].into();
+    /// let mut b = RingBuf::with_capacity(4);
+    /// b.write(&[0, 1]).unwrap();
+    ///
+    /// assert!(a == b);
+    /// assert!(!a.structural_eq(&b));
+    /// ```
+    pub fn structural_eq(&self, other: &RingBuf) -> bool {
+        check_valid!(self);
+
+        self.capacity() == other.capacity() && self == other
+    }
+
+    /// Returns `true` if `prefix` is a prefix of the contents of the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0x7f, b'E', b'L', b'F', 2].into();
+    /// assert!(rb.starts_with(b"\x7fELF"));
+    /// assert!(!rb.starts_with(b"ELF"));
+    /// ```
+    pub fn starts_with(&self, prefix: &[u8]) -> bool {
+        check_valid!(self);
+
+        if self.len() < prefix.len() {
+            return false;
+        }
+
+        let (first, second) = self.as_slices();
+        if prefix.len() <= first.len() {
+            first.starts_with(prefix)
+        }
+        else {
+            first == &prefix[..first.len()] && second.starts_with(&prefix[first.len()..])
+        }
+    }
+
+    /// Returns `true` if the bytes starting at the logical `offset` are equal to `pattern`,
+    /// comparing in place without copying. Returns `false` if fewer than
+    /// `offset + pattern.len()` bytes are buffered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"GET /index.html".to_vec().into();
+    /// assert!(rb.matches_at(4, b"/index"));
+    /// assert!(!rb.matches_at(4, b"/about"));
+    /// assert!(!rb.matches_at(10, b".html5"));
+    /// ```
+    pub fn matches_at(&self, offset: usize, pattern: &[u8]) -> bool {
+        check_valid!(self);
+
+        match offset.checked_add(pattern.len()) {
+            Some(end) if end <= self.len() => {},
+            _ => return false
+        }
+
+        let (first, second) = self.as_slices();
+        if offset >= first.len() {
+            let start = offset - first.len();
+            &second[start..start + pattern.len()] == pattern
+        }
+        else {
+            let head = min(first.len() - offset, pattern.len());
+            first[offset..offset + head] == pattern[..head]
+                && second[..pattern.len() - head] == pattern[head..]
+        }
+    }
+
+    /// Returns `true` if the `len` bytes starting at the logical `self_offset` are equal to
+    /// the `len` bytes of `other` starting at its logical `other_offset`, comparing in place
+    /// without copying. Returns `false` if either ring buffer doesn't contain enough bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let a: RingBuf = b"xxhello".to_vec().into();
+    /// let b: RingBuf = b"hello world".to_vec().into();
+    ///
+    /// assert!(a.window_eq(2, &b, 0, 5));
+    /// assert!(!a.window_eq(2, &b, 1, 5));
+    /// assert!(!a.window_eq(2, &b, 0, 6));
+    /// ```
+    pub fn window_eq(&self, self_offset: usize, other: &RingBuf, other_offset: usize, len: usize)
+        -> bool {
+        check_valid!(self);
+
+        let fits = |rb: &RingBuf, offset: usize| {
+            offset.checked_add(len).is_some_and(|end| end <= rb.len())
+        };
+        if !fits(self, self_offset) || !fits(other, other_offset) {
+            return false;
+        }
+
+        let (mut a, mut a_rest) = self.slices_at(self_offset, len);
+        let (mut b, mut b_rest) = other.slices_at(other_offset, len);
+        loop {
+            if a.is_empty() {
+                a = std::mem::take(&mut a_rest);
+            }
+            if b.is_empty() {
+                b = std::mem::take(&mut b_rest);
+            }
+            if a.is_empty() {
+                return true;
+            }
+
+            let n = min(a.len(), b.len());
+            if a[..n] != b[..n] {
+                return false;
+            }
+            a = &a[n..];
+            b = &b[n..];
+        }
+    }
+
+    /// Returns `true` if the contents of the ring buffer occupy a single contiguous run
+    /// of the underlying buffer, i.e. they don't wrap around its end.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// assert!(rb.is_contiguous());
+    ///
+    /// rb.advance_read_pos(3).unwrap();
+    /// rb.write(&[4, 5]).unwrap();
+    /// assert!(!rb.is_contiguous());
+    /// ```
+    pub fn is_contiguous(&self) -> bool {
+        check_valid!(self);
+
+        self.read_pos <= self.write_pos || self.write_pos == 0
+    }
+
+    /// Returns the number of bytes that can be read from the ring buffer
+    /// in a single contiguous run, starting at the read position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// rb.advance_read_pos(3).unwrap();
+    /// rb.write(&[4, 5]).unwrap();
+    ///
+    /// assert_eq!(rb.len(), 3);
+    /// assert_eq!(rb.available_contiguous_read(), 2);
+    /// ```
+    pub fn available_contiguous_read(&self) -> usize {
+        check_valid!(self);
+
+        if self.read_pos > self.write_pos {
+            self.data.len() - self.read_pos
+        }
+        else {
+            self.write_pos - self.read_pos
+        }
+    }
+
+    /// Returns the number of bytes that can be written to the ring buffer
+    /// in a single contiguous run, starting at the write position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2]).unwrap();
+    /// rb.advance_read_pos(2).unwrap();
+    ///
+    /// assert_eq!(rb.capacity() - rb.len(), 3);
+    /// assert_eq!(rb.available_contiguous_write(), 2);
+    /// ```
+    pub fn available_contiguous_write(&self) -> usize {
+        check_valid!(self);
+
+        if self.write_pos < self.read_pos {
+            self.read_pos - self.write_pos - 1
+        }
+        else if self.read_pos == 0 {
+            self.data.len() - self.write_pos - 1
+        }
+        else {
+            self.data.len() - self.write_pos
+        }
+    }
+
+    /// Returns the number of contiguous free bytes the ring buffer would have after moving
+    /// its contents to the start of the underlying buffer, which makes all free space contiguous.
+    /// This is always `remaining()`.
+    ///
+    /// Compare with [`RingBuf::contiguous_free_now`] to decide whether compacting,
+    /// e.g. through [`RingBuf::write_with_min`], is worth it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(6);
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// rb.advance_read_pos(2).unwrap();
+    ///
+    /// assert_eq!(rb.contiguous_free_now(), 3);
+    /// assert_eq!(rb.max_contiguous_free(), 4);
+    /// ```
+    pub fn max_contiguous_free(&self) -> usize {
+        check_valid!(self);
+
+        self.remaining()
+    }
+
+    /// Returns the number of bytes in the first contiguous run of free space,
+    /// starting at the write position, i.e. what can be written without wrapping right now.
+    /// This is the same as [`RingBuf::available_contiguous_write`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(6);
+    /// rb.write(&[0, 1]).unwrap();
+    /// assert_eq!(rb.contiguous_free_now(), 4);
+    /// ```
+    pub fn contiguous_free_now(&self) -> usize {
+        self.available_contiguous_write()
+    }
+
+    /// Returns the first contiguous run of free space, starting at the write position,
+    /// see [`RingBuf::available_contiguous_write`].
+    /// The slice is empty when the ring buffer is full.
+    ///
+    /// Bytes written to the slice become part of the contents once committed
+    /// with [`RingBuf::commit_write`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// let free = rb.contiguous_free_mut();
+    /// assert_eq!(free.len(), 4);
+    /// free[..2].copy_from_slice(&[0, 1]);
+    ///
+    /// rb.commit_write(2).unwrap();
+    /// assert!(rb.eq_slice(&[0, 1]));
+    /// ```
+    pub fn contiguous_free_mut(&mut self) -> &mut [u8] {
+        check_valid!(self);
+
+        let end = self.write_pos + self.available_contiguous_write();
+        &mut self.data[self.write_pos..end]
+    }
+
+    /// Writes `buf` to the ring buffer like `write`, but fails with `ErrorKind::WouldBlock`
+    /// instead of returning `Ok(0)` when the ring buffer is full and can't accept any bytes,
+    /// i.e. under `FullPolicy::Reject`. Writing an empty `buf` always returns `Ok(0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    ///
+    /// assert_eq!(rb.write_or_would_block(&[0, 1, 2, 3]).unwrap(), 3);
+    /// assert_eq!(rb.write_or_would_block(&[3]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// ```
+    pub fn write_or_would_block(&mut self, buf: &[u8]) -> Result<usize> {
+        check_valid!(self);
+
+        if !buf.is_empty() && self.remaining() == 0 && self.policy == FullPolicy::Reject {
+            return Err(Error::new(ErrorKind::WouldBlock, "Ring buffer is full."));
+        }
+
+        self.write(buf)
+    }
+
+    /// Reads from the ring buffer like `read`, but fails with `ErrorKind::WouldBlock`
+    /// instead of returning `Ok(0)` when the ring buffer is empty,
+    /// so generic code doesn't mistake a momentarily empty ring buffer for the end of the stream.
+    /// Reading into an empty `buf` always returns `Ok(0)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1].into();
+    ///
+    /// let mut buf = [0u8; 4];
+    /// assert_eq!(rb.read_or_would_block(&mut buf).unwrap(), 2);
+    /// assert_eq!(rb.read_or_would_block(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// ```
+    pub fn read_or_would_block(&mut self, buf: &mut [u8]) -> Result<usize> {
+        check_valid!(self);
+
+        if !buf.is_empty() && self.is_empty() {
+            return Err(Error::new(ErrorKind::WouldBlock, "Ring buffer is empty."));
+        }
+
+        self.read(buf)
+    }
+
+    /// Reads from the ring buffer like `read` and returns the number of bytes read,
+    /// without wrapping it in a `Result`, as reading from a ring buffer never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut buf = [0u8; 2];
+    /// assert_eq!(rb.read_available(&mut buf), 2);
+    /// assert_eq!(buf, [0, 1]);
+    /// assert_eq!(rb.read_available(&mut buf), 1);
+    /// assert_eq!(rb.read_available(&mut buf), 0);
+    /// ```
+    pub fn read_available(&mut self, buf: &mut [u8]) -> usize {
+        check_valid!(self);
+
+        self.read(buf).unwrap_or(0)
+    }
+
+    /// Writes the bytes of anything that can be viewed as a byte slice, like an array or a `Vec`,
+    /// to the ring buffer like `write`, and returns the number of bytes written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// assert_eq!(rb.write_from([0, 1]), 2);
+    /// assert_eq!(rb.write_from(vec![2, 3, 4]), 2);
+    /// assert!(rb.eq_slice(&[0, 1, 2, 3]));
+    /// ```
+    pub fn write_from<B: AsRef<[u8]>>(&mut self, b: B) -> usize {
+        check_valid!(self);
+
+        self.write(b.as_ref()).unwrap_or(0)
+    }
+
+    /// Reads from the ring buffer into anything that can be viewed as a mutable byte slice,
+    /// like `read_available`, and returns the number of bytes read.
+    /// Pass containers by mutable reference, otherwise the bytes are read into a temporary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut buf = [0u8; 2];
+    /// assert_eq!(rb.read_into(&mut buf), 2);
+    /// assert_eq!(buf, [0, 1]);
+    /// ```
+    pub fn read_into<B: AsMut<[u8]>>(&mut self, mut b: B) -> usize {
+        check_valid!(self);
+
+        self.read_available(b.as_mut())
+    }
+
+    /// Reads from the ring buffer like `read`, then moves the remaining contents to the start
+    /// of the underlying buffer if there are fewer than `capacity() * compact_threshold()` of them,
+    /// so the next write gets all free space as a single contiguous run.
+    /// The threshold defaults to a quarter of the capacity, see [`RingBuf::set_compact_threshold`].
+    ///
+    /// Compacting moves every byte of the underlying buffer, so it trades a copy now for fewer
+    /// split writes later. As this moves the read position, read bytes can't be rewound over afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(8);
+    /// rb.write(&[0, 1, 2, 3, 4, 5, 6]).unwrap();
+    ///
+    /// assert_eq!(rb.compacting_read(&mut [0u8; 6]).unwrap(), 6);
+    /// assert_eq!(rb.contiguous_free_now(), rb.remaining());
+    /// ```
+    pub fn compacting_read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        check_valid!(self);
+
+        let bytes_read = self.read(buf)?;
+        let threshold = self.capacity() as f64 * f64::from(self.compact_threshold);
+        if self.read_pos != 0 && (self.len() as f64) < threshold {
+            self.compact();
+            self.consumed = 0;
+        }
+
+        Ok(bytes_read)
+    }
+
+    /// Continues filling `buf` from the ring buffer, starting at `buf[already..]`,
+    /// and returns the new number of filled bytes, so a read can be resumed
+    /// across refills of the ring buffer until it returns `buf.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `already` is larger than `buf.len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// let mut buf = [0u8; 6];
+    ///
+    /// rb.write(&[0, 1, 2, 3]).unwrap();
+    /// let filled = rb.read_progress(&mut buf, 0);
+    /// assert_eq!(filled, 4);
+    ///
+    /// rb.write(&[4, 5, 6]).unwrap();
+    /// assert_eq!(rb.read_progress(&mut buf, filled), 6);
+    /// assert_eq!(buf, [0, 1, 2, 3, 4, 5]);
+    /// assert!(rb.eq_slice(&[6]));
+    /// ```
+    pub fn read_progress(&mut self, buf: &mut [u8], already: usize) -> usize {
+        check_valid!(self);
+
+        already + self.read_available(&mut buf[already..])
+    }
+
+    /// Writes at most `max` bytes from `buf` to the ring buffer,
+    /// i.e. `min(buf.len(), max, remaining())` bytes, and returns the number of bytes written.
+    /// This never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    ///
+    /// assert_eq!(rb.write_at_most(&[0, 1, 2, 3, 4], 3), 3);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn write_at_most(&mut self, buf: &[u8], max: usize) -> usize {
+        check_valid!(self);
+
+        self.write_truncating(&buf[..min(buf.len(), max)])
+    }
+
+    /// Writes as many bytes of `buf` as fit in the free space to the ring buffer
+    /// and returns the number of bytes written, along with whether `buf` was truncated,
+    /// i.e. whether fewer than `buf.len()` bytes were written.
+    /// This never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// assert_eq!(rb.write_checked(&[0, 1]), (2, false));
+    /// assert_eq!(rb.write_checked(&[2, 3, 4]), (2, true));
+    /// assert!(rb.eq_slice(&[0, 1, 2, 3]));
+    /// ```
+    pub fn write_checked(&mut self, buf: &[u8]) -> (usize, bool) {
+        check_valid!(self);
+
+        let written = self.write_truncating(buf);
+        (written, written < buf.len())
+    }
+
+    /// Writes the bytes of `iter` to the ring buffer until it ends or the ring buffer is full.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if `iter` has bytes left once the ring buffer is full,
+    /// keeping the bytes that were written. The first byte that didn't fit is taken from `iter`
+    /// and discarded. This never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// rb.try_extend(0..2).unwrap();
+    /// assert!(rb.try_extend(2..5).is_err());
+    /// assert!(rb.eq_slice(&[0, 1, 2, 3]));
+    /// ```
+    pub fn try_extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) -> Result<()> {
+        check_valid!(self);
+
+        for byte in iter {
+            if self.write_truncating(&[byte]) == 0 {
+                return Err(RingError::Full.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the last `min(buf.len(), capacity())` bytes of `buf` to the ring buffer,
+    /// dropping as many of the oldest buffered bytes as needed to make room,
+    /// and returns the number of buffered bytes that were dropped.
+    ///
+    /// This behaves like `write` with `FullPolicy::OverwriteOldest`,
+    /// regardless of the [`FullPolicy`] of the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// assert_eq!(rb.force_write(&[3, 4]), 2);
+    /// assert!(rb.eq_slice(&[2, 3, 4]));
+    /// ```
+    pub fn force_write(&mut self, buf: &[u8]) -> usize {
+        check_valid!(self);
+
+        self.overwrite(buf).0
+    }
+
+    /// Writes `buf` to the ring buffer exactly like `write`, but also returns whether
+    /// the written bytes wrapped around the end of the underlying buffer,
+    /// i.e. whether they were copied into two separate ranges of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.write(&[0, 1, 2]).unwrap();
+    /// rb.advance_read_pos(2).unwrap();
+    ///
+    /// assert_eq!(rb.write_reporting(&[3]), (1, false));
+    /// assert_eq!(rb.write_reporting(&[4, 5]), (2, true));
+    /// assert!(rb.eq_slice(&[2, 3, 4, 5]));
+    /// ```
+    pub fn write_reporting(&mut self, buf: &[u8]) -> (usize, bool) {
+        check_valid!(self);
+
+        if buf.len() <= self.remaining() {
+            return self.write_truncating_reporting(buf);
+        }
+
+        match self.policy {
+            FullPolicy::Reject => {
+                debug_assert!(!self.panic_on_truncate,
+                    "write truncated {} bytes to {}", buf.len(), self.remaining());
+                self.write_truncating_reporting(buf)
+            },
+            FullPolicy::OverwriteOldest => (buf.len(), self.overwrite(buf).1),
+            FullPolicy::Grow => {
+                self.grow(self.len().saturating_add(buf.len()));
+                self.write_truncating_reporting(buf)
+            }
+        }
+    }
+
+    /// Writes the last bytes of `buf` that fit in the free space to the ring buffer,
+    /// i.e. the last `min(buf.len(), remaining())` bytes, and returns the number of bytes written.
+    ///
+    /// This is the counterpart of writing with `FullPolicy::Reject`, which keeps the first bytes
+    /// that fit instead. Useful for streams where only the most recent data matters.
+    /// Existing contents are never dropped, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    ///
+    /// assert_eq!(rb.write_tail(&[0, 1, 2, 3, 4]), 3);
+    /// assert!(rb.eq_slice(&[2, 3, 4]));
+    /// ```
+    pub fn write_tail(&mut self, buf: &[u8]) -> usize {
+        check_valid!(self);
+
+        let skip = buf.len() - min(buf.len(), self.remaining());
+        self.write_truncating(&buf[skip..])
+    }
+
+    /// Writes all of `buf` to the ring buffer, or nothing at all.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if `buf` doesn't fit in the free space,
+    /// in which case the ring buffer is left unchanged.
+    /// Unlike `write`, this never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(3);
+    ///
+    /// rb.extend_from_slice_exact(&[0, 1]).unwrap();
+    /// assert!(rb.extend_from_slice_exact(&[2, 3]).is_err());
+    /// assert!(rb.eq_slice(&[0, 1]));
+    /// ```
+    pub fn extend_from_slice_exact(&mut self, buf: &[u8]) -> Result<()> {
+        check_valid!(self);
+
+        if buf.len() > self.remaining() {
+            return Err(RingError::Full.into());
+        }
+
+        self.write_truncating(buf);
+        Ok(())
+    }
+
+    /// Writes `slices` to the ring buffer in order until it is full,
+    /// and returns the total number of bytes written.
+    /// This never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// assert_eq!(rb.write_slices(&[&[0, 1], &[2]]), 3);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn write_slices(&mut self, slices: &[&[u8]]) -> usize {
+        check_valid!(self);
+
+        let mut written = 0;
+        for slice in slices {
+            written += self.write_truncating(slice);
+            if self.remaining() == 0 {
+                break;
+            }
+        }
+
+        written
+    }
+
+    /// Writes all of `bufs` to the ring buffer in order, or nothing at all.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if their total length exceeds the free space,
+    /// in which case the ring buffer is left unchanged.
+    /// Like `extend_from_slice_exact`, this never drops or grows, regardless of the [`FullPolicy`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::IoSlice;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// rb.write_vectored_all(&[IoSlice::new(&[0]), IoSlice::new(&[1, 2])]).unwrap();
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    ///
+    /// assert!(rb.write_vectored_all(&[IoSlice::new(&[3]), IoSlice::new(&[4])]).is_err());
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn write_vectored_all(&mut self, bufs: &[IoSlice<'_>]) -> Result<()> {
+        check_valid!(self);
+
+        let total = bufs.iter().fold(0usize, |total, buf| total.saturating_add(buf.len()));
+        if total > self.remaining() {
+            return Err(RingError::Full.into());
+        }
+
+        for buf in bufs {
+            self.write_truncating(buf);
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the contents of the ring buffer with `buf`,
+    /// leaving the read position at the start of the underlying buffer.
+    ///
+    /// Fails with `ErrorKind::WriteZero` if `buf` is larger than the capacity,
+    /// in which case the ring buffer is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// rb.replace_contents(&[3, 4]).unwrap();
+    /// assert!(rb.eq_slice(&[3, 4]));
+    ///
+    /// assert!(rb.replace_contents(&[0, 1, 2, 3]).is_err());
+    /// assert!(rb.eq_slice(&[3, 4]));
+    /// ```
+    pub fn replace_contents(&mut self, buf: &[u8]) -> Result<()> {
+        check_valid!(self);
+
+        if buf.len() > self.capacity() {
+            return Err(RingError::Overflow.into());
+        }
+
+        self.clear();
+        self.write_truncating(buf);
+        Ok(())
+    }
+
+    /// Writes to the ring buffer by calling `f` with the first contiguous run of free space,
+    /// see [`RingBuf::available_contiguous_write`].
+    /// `f` returns the number of bytes it wrote to the slice, which is also returned by this method,
+    /// and the write position is advanced by that amount.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a count larger than the length of the slice it was given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    ///
+    /// let written = rb.write_with(|buf| {
+    ///     buf[..3].copy_from_slice(&[0, 1, 2]);
+    ///     3
+    /// });
+    /// assert_eq!(written, 3);
+    /// assert!(rb.eq_slice(&[0, 1, 2]));
+    /// ```
+    pub fn write_with<F: FnOnce(&mut [u8]) -> usize>(&mut self, f: F) -> usize {
+        check_valid!(self);
+
+        let free = self.contiguous_free_mut();
+        let len = free.len();
+        let written = f(free);
+        assert!(written <= len, "write_with closure reported more bytes than it was given");
+
+        self.commit_written(written);
+        written
+    }
+
+    /// Like [`RingBuf::write_with`], but for closures that can fail, such as reading from a socket.
+    ///
+    /// On success, `f` returns the number of bytes it wrote to the slice along with a value
+    /// which is passed on to the caller, and the write position is advanced by that count.
+    /// If `f` fails, its error is returned and the write position is left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a count larger than the length of the slice it was given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(10);
+    /// let mut reader = &b"abc"[..];
+    ///
+    /// let read = rb.write_with_result(|buf| reader.read(buf).map(|n| (n, n))).unwrap();
+    /// assert_eq!(read, 3);
+    /// assert!(rb.eq_slice(b"abc"));
+    /// ```
+    pub fn write_with_result<F, T, E>(&mut self, f: F) -> std::result::Result<T, E>
+    where
+        F: FnOnce(&mut [u8]) -> std::result::Result<(usize, T), E>
+    {
+        check_valid!(self);
+
+        let free = self.contiguous_free_mut();
+        let len = free.len();
+        let (written, value) = f(free)?;
+        assert!(written <= len, "write_with_result closure reported more bytes than it was given");
+
+        self.commit_written(written);
+        Ok(value)
+    }
+
+    /// Reads exactly `n` bytes from `r` straight into the free space of the ring buffer.
+    ///
+    /// Fails with `ErrorKind::InvalidInput`, without reading anything, if `n` is larger
+    /// than `remaining()`. Fails with `ErrorKind::UnexpectedEof` if `r` ends before `n` bytes
+    /// were read, and with any error other than `ErrorKind::Interrupted` returned by `r`;
+    /// in both cases the bytes read so far stay in the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(8);
+    /// let mut reader = &b"hello world"[..];
+    ///
+    /// rb.fill_from_exact(&mut reader, 5).unwrap();
+    /// assert!(rb.eq_slice(b"hello"));
+    /// assert_eq!(reader, b" world");
+    /// ```
+    pub fn fill_from_exact<R: Read>(&mut self, r: &mut R, n: usize) -> Result<()> {
+        check_valid!(self);
+
+        if n > self.remaining() {
+            return Err(RingError::CommitPastFree.into());
+        }
+
+        let mut left = n;
+        while left > 0 {
+            match self.write_with_result(|buf| {
+                let len = min(buf.len(), left);
+                r.read(&mut buf[..len]).map(|read| (read, read))
+            }) {
+                Ok(0) => return Err(Error::new(ErrorKind::UnexpectedEof,
+                    "Reader ended before all bytes were read.")),
+                Ok(read) => left -= read,
+                Err(err) if err.kind() == ErrorKind::Interrupted => {},
+                Err(err) => return Err(err)
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like [`RingBuf::write_with`], but makes sure that `f` is given at least `min` bytes
+    /// of contiguous free space when the ring buffer has that much free space in total.
+    ///
+    /// If the first contiguous run of free space is shorter than `min` while `remaining()`
+    /// is at least `min`, the contents are first moved to the start of the underlying buffer
+    /// so all free space becomes contiguous. The contents are only moved when that is
+    /// what it takes to honor `min`; otherwise this behaves exactly like `write_with`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(6);
+    /// rb.write(&[0, 1, 2, 3, 4]).unwrap();
+    /// rb.advance_read_pos(3).unwrap();
+    /// assert_eq!(rb.available_contiguous_write(), 2);
+    ///
+    /// let written = rb.write_with_min(4, |buf| {
+    ///     assert!(buf.len() >= 4);
+    ///     buf[..4].copy_from_slice(&[5, 6, 7, 8]);
+    ///     4
+    /// });
+    /// assert_eq!(written, 4);
+    /// assert!(rb.eq_slice(&[3, 4, 5, 6, 7, 8]));
+    /// ```
+    pub fn write_with_min<F: FnOnce(&mut [u8]) -> usize>(&mut self, min: usize, f: F) -> usize {
+        check_valid!(self);
+
+        if self.available_contiguous_write() < min && self.remaining() >= min {
+            self.compact();
+        }
+
+        self.write_with(f)
+    }
+
+    /// Advances the write position by `count`, making the first `count` bytes of the free space
+    /// part of the contents as they are, e.g. after they were filled in directly.
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `count` is larger than `remaining()`,
+    /// in which case the write position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    ///
+    /// rb.commit_write(3).unwrap();
+    /// assert_eq!(rb.len(), 3);
+    /// assert!(rb.commit_write(2).is_err());
+    /// ```
+    pub fn commit_write(&mut self, count: usize) -> Result<()> {
+        check_valid!(self);
+
+        if count > self.remaining() {
+            return Err(RingError::CommitPastFree.into());
+        }
+
+        self.commit_written(count);
+        Ok(())
+    }
+
+    /// Advances the read position by `count`, the read side counterpart of [`RingBuf::commit_write`].
+    ///
+    /// Fails with `ErrorKind::InvalidInput` if `count` is larger than `len()`,
+    /// in which case the read position is left unchanged, like [`RingBuf::advance_read_pos`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// rb.commit_read(2).unwrap();
+    /// assert!(rb.eq_slice(&[2]));
+    /// assert!(rb.commit_read(2).is_err());
+    /// ```
+    pub fn commit_read(&mut self, count: usize) -> Result<()> {
+        self.advance_read_pos(count)
+    }
+
+    /// Consumes the bytes a [`PeekCursor`] advanced over, as recorded by [`PeekCursor::into_mark`].
+    ///
+    /// The mark is only meaningful as long as nothing was read from the ring buffer since
+    /// the cursor was created. Fails with `ErrorKind::InvalidInput` if the mark is past
+    /// `len()`, in which case the read position is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    ///
+    /// let mut cursor = rb.peek_cursor();
+    /// cursor.skip(2);
+    /// let mark = cursor.into_mark();
+    /// assert_eq!(mark.offset(), 2);
+    /// rb.commit(mark).unwrap();
+    /// assert!(rb.eq_slice(&[2]));
+    /// ```
+    pub fn commit(&mut self, mark: PeekMark) -> Result<()> {
+        self.advance_read_pos(mark.offset)
+    }
+
+    /// Returns the number of times `byte` occurs in the ring buffer.
+    ///
+    /// With the `memchr` feature enabled, the counting uses the `memchr` crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"a\nb\nc".to_vec().into();
+    /// assert_eq!(rb.count_byte(b'\n'), 2);
+    /// ```
+    pub fn count_byte(&self, byte: u8) -> usize {
+        check_valid!(self);
+
+        let (first, second) = self.as_slices();
+        memchr_count(byte, first) + memchr_count(byte, second)
+    }
+
+    /// Returns an iterator over the bytes in the ring buffer, in read order,
+    /// without consuming them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// assert_eq!(rb.peek_iter().map(u32::from).sum::<u32>(), 3);
+    /// assert_eq!(rb.len(), 3);
+    /// ```
+    pub fn peek_iter(&self) -> PeekIter<'_> {
+        check_valid!(self);
+
+        PeekIter {
+            rb: self,
+            pos: self.read_pos,
+            len: self.len()
+        }
+    }
+
+    /// Returns a reader over the contents of the ring buffer that keeps its own read position,
+    /// so reading from it doesn't consume anything from the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"hello".to_vec().into();
+    ///
+    /// let mut s = String::new();
+    /// rb.reader_view().read_to_string(&mut s).unwrap();
+    /// assert_eq!(s, "hello");
+    /// assert_eq!(rb.len(), 5);
+    /// ```
+    pub fn reader_view(&self) -> ReaderView<'_> {
+        check_valid!(self);
+
+        ReaderView {
+            rb: self,
+            offset: 0
+        }
+    }
+
+    /// Returns a cursor for parsing the contents of the ring buffer byte by byte
+    /// without consuming them.
+    ///
+    /// The cursor only tracks its own offset, so dropping it leaves the ring buffer unchanged
+    /// and a parser can bail out on incomplete data and retry once more bytes have been written.
+    /// To consume what was parsed, pass [`PeekCursor::into_mark`] to [`RingBuf::commit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![1, 0x12, 0x34, 9].into();
+    ///
+    /// let mut cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.read_u8(), Some(1));
+    /// assert_eq!(cursor.read_u16_be(), Some(0x1234));
+    /// let mark = cursor.into_mark();
+    /// rb.commit(mark).unwrap();
+    ///
+    /// let mut cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.read_u16_be(), None);
+    /// drop(cursor);
+    /// assert!(rb.eq_slice(&[9]));
+    /// ```
+    pub fn peek_cursor(&self) -> PeekCursor<'_> {
+        check_valid!(self);
+
+        PeekCursor {
+            rb: self,
+            offset: 0
+        }
+    }
+
+    /// Turns the ring buffer into a writer that collects written bytes in the ring buffer
+    /// and writes them to `sink` when it's full or flushed, like `std::io::BufWriter`.
+    ///
+    /// Writes that are at least as large as the capacity bypass the ring buffer.
+    /// Bytes already in the ring buffer are written to `sink` first.
+    /// Unlike `BufWriter`, the ring buffer isn't flushed when the writer is dropped,
+    /// so call `flush` or [`BufWriterRing::into_inner`] when done.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+    ///
+    /// writer.write_all(b"ab").unwrap();
+    /// assert!(writer.get_ref().is_empty());
+    ///
+    /// writer.write_all(b"cde").unwrap();
+    /// assert_eq!(writer.get_ref(), b"ab");
+    ///
+    /// assert_eq!(writer.into_inner().unwrap(), b"abcde");
+    /// ```
+    pub fn into_buffered_writer<W: Write>(self, sink: W) -> BufWriterRing<W> {
+        check_valid!(self);
+
+        BufWriterRing {
+            ring: self,
+            sink
+        }
+    }
+
+    /// Chains this ring buffer with `other` into a reader that reads all bytes of this
+    /// ring buffer, followed by those of `other`.
+    ///
+    /// Unlike `Read::chain`, which this takes precedence over, a single read can span
+    /// both ring buffers and the chain knows its total length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let header: RingBuf = vec![0, 1].into();
+    /// let body: RingBuf = vec![2, 3, 4].into();
+    ///
+    /// let mut chain = header.chain(body);
+    /// assert_eq!(chain.len(), 5);
+    ///
+    /// let mut buf = [0u8; 3];
+    /// chain.read_exact(&mut buf).unwrap();
+    /// assert_eq!(buf, [0, 1, 2]);
+    /// assert_eq!(chain.len(), 2);
+    /// ```
+    pub fn chain(self, other: RingBuf) -> Chain {
+        check_valid!(self);
+
+        Chain {
+            first: self,
+            second: other
+        }
+    }
+
+    /// Turns the ring buffer into a reader that reads at most `limit` bytes from it
+    /// before signaling end of file.
+    ///
+    /// This takes precedence over `Read::take`, but unlike it the ring buffer,
+    /// with whatever bytes are left in it, can be got back with [`Take::into_inner`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    ///
+    /// let mut take = rb.take(3);
+    /// let mut buf = Vec::new();
+    /// take.read_to_end(&mut buf).unwrap();
+    /// assert_eq!(buf, [0, 1, 2]);
+    ///
+    /// assert!(take.into_inner().eq_slice(&[3, 4]));
+    /// ```
+    pub fn take(self, limit: u64) -> Take {
+        check_valid!(self);
+
+        Take {
+            inner: self,
+            limit
+        }
+    }
+
+    /// Returns an iterator over all contiguous windows of length `size` of the
+    /// contents of the ring buffer. The windows overlap and are yielded in read order.
+    /// If the ring buffer is shorter than `size`, the iterator returns no values.
+    ///
+    /// As a window may wrap around the end of the underlying buffer,
+    /// each window is copied into a newly allocated `Vec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2, 3].into();
+    /// let mut iter = rb.windows(3);
+    /// assert_eq!(iter.next().unwrap(), [0, 1, 2]);
+    /// assert_eq!(iter.next().unwrap(), [1, 2, 3]);
+    /// assert!(iter.next().is_none());
+    /// ```
+    pub fn windows(&self, size: usize) -> Windows<'_> {
+        check_valid!(self);
+
+        assert_ne!(size, 0, "window size must be non-zero");
+
+        Windows {
+            rb: self,
+            size,
+            offset: 0
+        }
+    }
+
+    /// Returns an iterator over successive, non-overlapping `N`-byte blocks of the contents
+    /// of the ring buffer, in read order, as fixed-size arrays.
+    ///
+    /// The last `len() % N` bytes don't make up a full block and aren't yielded.
+    /// Blocks that wrap around the end of the underlying buffer are assembled into the array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    /// let mut iter = rb.peek_blocks::<2>();
+    /// assert_eq!(iter.next(), Some([0, 1]));
+    /// assert_eq!(iter.next(), Some([2, 3]));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn peek_blocks<const N: usize>(&self) -> Blocks<'_, N> {
+        check_valid!(self);
+
+        assert_ne!(N, 0, "block size must be non-zero");
+
+        Blocks {
+            rb: self,
+            offset: 0
+        }
+    }
+
+    /// Returns an iterator that slides a window of length `window` over the contents
+    /// of the ring buffer one byte at a time, without consuming them.
+    ///
+    /// For every byte in read order it yields an `(outgoing, incoming)` pair, where `incoming`
+    /// is the byte entering the window and `outgoing` is the byte leaving it,
+    /// or `None` while the window is still filling up. This is what rolling hashes,
+    /// such as the one computed by [`RingBuf::rolling_hash`], are built on.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let pairs: Vec<_> = rb.slide(2).collect();
+    /// assert_eq!(pairs, [(None, 0), (None, 1), (Some(0), 2)]);
+    /// ```
+    pub fn slide(&self, window: usize) -> Slide<'_> {
+        check_valid!(self);
+
+        assert_ne!(window, 0, "window size must be non-zero");
+
+        Slide {
+            incoming: self.peek_iter(),
+            outgoing: self.peek_iter(),
+            window,
+            seen: 0
+        }
+    }
+
+    /// Returns the polynomial rolling hash of every window of length `window`
+    /// of the contents of the ring buffer, in read order, without consuming them.
+    /// If the ring buffer is shorter than `window`, the result is empty.
+    ///
+    /// The hash of the bytes `b[0], ..., b[window - 1]` is the sum of `b[i] * 257^(window - 1 - i)`,
+    /// computed with wrapping `u64` arithmetic. It's updated in constant time per byte
+    /// with [`RingBuf::slide`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![1, 2, 1, 2].into();
+    /// let hashes = rb.rolling_hash(2);
+    /// assert_eq!(hashes, [257 + 2, 2 * 257 + 1, 257 + 2]);
+    /// ```
+    pub fn rolling_hash(&self, window: usize) -> Vec<u64> {
+        const BASE: u64 = 257;
+
+        check_valid!(self);
+
+        let outgoing_factor = (0..window).fold(1u64, |factor, _| factor.wrapping_mul(BASE));
+        let mut hashes = Vec::with_capacity((self.len() + 1).saturating_sub(window));
+        let mut hash = 0u64;
+        for (i, (outgoing, incoming)) in self.slide(window).enumerate() {
+            hash = hash.wrapping_mul(BASE).wrapping_add(u64::from(incoming));
+            if let Some(outgoing) = outgoing {
+                hash = hash.wrapping_sub(u64::from(outgoing).wrapping_mul(outgoing_factor));
+            }
+
+            if i + 1 >= window {
+                hashes.push(hash);
+            }
+        }
+
+        hashes
+    }
+
+    /// Returns an iterator over the segments of the contents of the ring buffer separated by `delim`,
+    /// without consuming them. Like `slice::split`, the delimiters aren't included
+    /// and the segment after the last delimiter is always yielded, even if it's empty.
+    ///
+    /// As a segment may wrap around the end of the underlying buffer,
+    /// each segment is copied into a newly allocated `Vec`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = b"ab,c,,d".to_vec().into();
+    /// let segments: Vec<Vec<u8>> = rb.split(b',').collect();
+    /// assert_eq!(segments, [&b"ab"[..], b"c", b"", b"d"]);
+    /// assert_eq!(rb.len(), 7);
+    /// ```
+    pub fn split(&self, delim: u8) -> Split<'_> {
+        check_valid!(self);
+
+        Split {
+            rb: self,
+            delim,
+            offset: 0,
+            finished: false
+        }
+    }
+
+    /// Returns an iterator that consumes complete lines from the ring buffer,
+    /// including the trailing `\n`.
+    ///
+    /// Iteration stops as soon as no complete line is buffered, leaving a trailing partial line
+    /// in the ring buffer so it can be completed by a later write.
+    /// A line that isn't valid UTF-8 is still consumed, and yields an `ErrorKind::InvalidData` error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(16);
+    /// rb.write(b"foo\nbar\nba").unwrap();
+    ///
+    /// let lines: Vec<String> = rb.lines().map(Result::unwrap).collect();
+    /// assert_eq!(lines, ["foo\n", "bar\n"]);
+    /// assert!(rb.eq_slice(b"ba"));
+    ///
+    /// rb.write(b"z\n").unwrap();
+    /// assert_eq!(rb.lines().next().unwrap().unwrap(), "baz\n");
+    /// ```
+    pub fn lines(&mut self) -> Lines<'_> {
+        check_valid!(self);
+
+        Lines {
+            rb: self
+        }
+    }
+
+    /// Returns the contents of the ring buffer as two slices, in read order.
+    /// The second slice is empty unless the contents wrap around the end of the buffer.
+    fn as_slices(&self) -> (&[u8], &[u8]) {
+        if self.read_pos > self.write_pos {
+            (&self.data[self.read_pos..], &self.data[..self.write_pos])
+        }
+        else {
+            (&self.data[self.read_pos..self.write_pos], &[])
+        }
+    }
+
+    /// Records the current length if it's the highest so far.
+    fn update_high_water_mark(&mut self) {
+        self.high_water_mark = self.high_water_mark.max(self.len());
+    }
+
+    /// Moves the read position back by `count`, which has to be at most `rewindable()`.
+    fn unconsume(&mut self, count: usize) {
+        debug_assert!(count <= self.rewindable());
+
+        self.read_pos = if count > self.read_pos {
+            self.data.len() + self.read_pos - count
+        }
+        else {
+            self.read_pos - count
+        };
+        self.read_bit_pos = 0;
+        self.consumed -= count;
+        self.total_read = self.total_read.wrapping_sub(count);
+    }
+
+    /// Advances the read position by `count`, which has to be at most `len()`,
+    /// reporting `BufEvent::BecameEmpty` if this empties the ring buffer.
+    fn consume(&mut self, count: usize) {
+        self.move_read_pos(count);
+        if count > 0 && self.is_empty() {
+            self.fire_event(BufEvent::BecameEmpty);
+        }
+    }
+
+    /// Advances the read position by `count`, which has to be at most `len()`,
+    /// without reporting events.
+    fn move_read_pos(&mut self, count: usize) {
+        debug_assert!(count <= self.len());
+
+        if count > 0 {
+            self.read_bit_pos = 0;
+        }
+
+        self.read_pos = self.wrap_pos(self.read_pos + count);
+        self.consumed = self.consumed.saturating_add(count);
+        self.total_read = self.total_read.wrapping_add(count);
+        if self.reset_on_empty {
+            self.reset_if_empty();
+        }
+    }
+
+    /// Moves the write position back by `count`, which has to be at most `len()`.
+    fn unwrite(&mut self, count: usize) {
+        debug_assert!(count <= self.len());
+
+        self.write_pos = if count > self.write_pos {
+            self.data.len() + self.write_pos - count
+        }
+        else {
+            self.write_pos - count
+        };
+        if count > 0 {
+            self.read_bit_pos = 0;
+        }
+        if self.reset_on_empty {
+            self.reset_if_empty();
+        }
+        if count > 0 && self.is_empty() {
+            self.fire_event(BufEvent::BecameEmpty);
+        }
+    }
+
+    /// Calls the event handler, if any, with `event`.
+    fn fire_event(&mut self, event: BufEvent) {
+        if let Some(handler) = self.event_handler.as_mut() {
+            handler(event);
+        }
+    }
+
+    /// Moves both positions back to the start of the underlying buffer if the ring buffer is empty,
+    /// so the next write gets all free space as a single contiguous run.
+    /// As this moves the read position, read bytes can't be rewound over anymore afterwards.
+    fn reset_if_empty(&mut self) {
+        if self.read_pos == self.write_pos {
+            self.read_pos = 0;
+            self.write_pos = 0;
+            self.consumed = 0;
+        }
+    }
+
+    /// Writes the last `min(buf.len(), capacity())` bytes of `buf`, dropping the oldest bytes
+    /// as needed, and returns the number of dropped bytes and whether the write wrapped.
+    /// Only reports `BufEvent::BecameFull` if the ring buffer wasn't full before.
+    fn overwrite(&mut self, buf: &[u8]) -> (usize, bool) {
+        let was_full = self.is_full();
+        let tail = &buf[buf.len() - min(buf.len(), self.capacity())..];
+        let overflow = tail.len().saturating_sub(self.remaining());
+        self.move_read_pos(overflow);
+        let wrapped = self.fill_free(tail).1;
+        if !was_full && self.is_full() {
+            self.fire_event(BufEvent::BecameFull);
+        }
+
+        (overflow, wrapped)
+    }
+
+    /// Writes as much of `buf` as fits in the free space, returning the number of bytes written.
+    fn write_truncating(&mut self, buf: &[u8]) -> usize {
+        self.write_truncating_reporting(buf).0
+    }
+
+    /// Like `write_truncating`, but also returns whether the write wrapped around
+    /// the end of the underlying buffer.
+    fn write_truncating_reporting(&mut self, buf: &[u8]) -> (usize, bool) {
+        let (written, wrapped) = self.fill_free(buf);
+        if written > 0 && self.is_full() {
+            self.fire_event(BufEvent::BecameFull);
+        }
+
+        (written, wrapped)
+    }
+
+    /// Copies as much of `buf` as fits into the free space and advances the write position,
+    /// without reporting events. Returns the number of bytes written and whether the write wrapped.
+    fn fill_free(&mut self, buf: &[u8]) -> (usize, bool) {
+        let to_write = min(self.remaining(), buf.len());
+        let wrapped = self.data.len() - self.write_pos < to_write;
+        self.copy_in(self.write_pos, &buf[..to_write]);
+        self.write_pos = self.wrap_pos(self.write_pos + to_write);
+        self.update_high_water_mark();
+        (to_write, wrapped)
+    }
+
+    /// Advances the write position over `count` bytes already written to the free space,
+    /// reporting `BufEvent::BecameFull` if this fills the ring buffer.
+    fn commit_written(&mut self, count: usize) {
+        debug_assert!(count <= self.remaining());
+
+        self.write_pos = self.wrap_pos(self.write_pos + count);
+        self.update_high_water_mark();
+        if count > 0 && self.is_full() {
+            self.fire_event(BufEvent::BecameFull);
+        }
+    }
+
+    /// Grows the underlying buffer to hold at least `min_capacity` bytes,
+    /// at least multiplying the capacity by the growth factor so repeated growth is amortized.
+    fn grow(&mut self, min_capacity: usize) {
+        if min_capacity <= self.capacity() {
+            return;
+        }
+
+        let scaled = (self.capacity() as f64 * f64::from(self.growth_factor)).ceil() as usize;
+        let new_capacity = min_capacity.max(scaled);
+        self.reallocate(new_capacity);
+    }
+
+    /// Moves the contents to a new underlying buffer with the given capacity,
+    /// zeroing the old one first if zeroing on clear is enabled.
+    fn reallocate(&mut self, new_capacity: usize) {
+        let mut old = self.swap_backing(vec![0; Self::backing_len(new_capacity)].into_boxed_slice());
+        if self.zero_on_clear {
+            old.fill(0);
+        }
+    }
+
+    /// Moves the contents to the start of the underlying buffer, so the read position becomes 0
+    /// and all free space is contiguous.
+    fn compact(&mut self) {
+        let len = self.len();
+        self.data.rotate_left(self.read_pos);
+        self.read_pos = 0;
+        self.write_pos = len;
+    }
+
+    /// Returns the logical offset of the first occurrence of `byte`
+    /// at or after the logical offset `start`.
+    fn find_byte(&self, byte: u8, start: usize) -> Option<usize> {
+        let (first, second) = self.as_slices();
+        if start < first.len() {
+            memchr(byte, &first[start..]).map(|i| start + i)
+                .or_else(|| memchr(byte, second).map(|i| first.len() + i))
+        }
+        else {
+            let start = min(start - first.len(), second.len());
+            memchr(byte, &second[start..]).map(|i| first.len() + start + i)
+        }
+    }
+
+    /// Returns the logical offset of the first occurrence of the non-empty sequence `pattern`.
+    fn find_sequence(&self, pattern: &[u8]) -> Option<usize> {
+        let mut start = 0;
+        while let Some(i) = self.find_byte(pattern[0], start) {
+            if self.matches_at(i, pattern) {
+                return Some(i);
+            }
+
+            start = i + 1;
+        }
+
+        None
+    }
+
+    /// Returns the `len` bytes starting at the logical `offset` as two slices, in read order.
+    /// The caller has to make sure that `offset + len <= self.len()`.
+    fn slices_at(&self, offset: usize, len: usize) -> (&[u8], &[u8]) {
+        debug_assert!(offset + len <= self.len());
+
+        let start = self.wrap_pos(self.read_pos + offset);
+        let bytes_until_end = self.data.len() - start;
+        if bytes_until_end < len {
+            (&self.data[start..], &self.data[..len - bytes_until_end])
+        }
+        else {
+            (&self.data[start..start + len], &[])
+        }
+    }
+
+    /// Copies `buf.len()` bytes starting at the logical `offset` into `buf`.
+    /// The caller has to make sure that `offset + buf.len() <= self.len()`.
+    fn copy_at(&self, offset: usize, buf: &mut [u8]) {
+        debug_assert!(offset + buf.len() <= self.len());
+
+        let start = self.wrap_pos(self.read_pos + offset);
+        let bytes_until_end = self.data.len() - start;
+        if bytes_until_end < buf.len() {
+            let (head, tail) = buf.split_at_mut(bytes_until_end);
+            head.copy_from_slice(&self.data[start..]);
+            tail.copy_from_slice(&self.data[..tail.len()]);
+        }
+        else {
+            buf.copy_from_slice(&self.data[start..start + buf.len()]);
+        }
+    }
+
+    /// Copies `buf` into the underlying buffer starting at index `start`,
+    /// wrapping around the end of the underlying buffer if needed.
+    /// The caller has to make sure that `buf.len() <= self.data.len()`.
+    fn copy_in(&mut self, start: usize, buf: &[u8]) {
+        debug_assert!(buf.len() <= self.data.len());
+
+        let bytes_until_end = self.data.len() - start;
+        if bytes_until_end < buf.len() {
+            let (head, tail) = buf.split_at(bytes_until_end);
+            self.data[start..].copy_from_slice(head);
+            self.data[..tail.len()].copy_from_slice(tail);
+        }
+        else {
+            self.data[start..start + buf.len()].copy_from_slice(buf);
+        }
+    }
+
+    /// Maps a position that may have run past the end of the underlying buffer
+    /// back into it. `pos` has to be smaller than twice the length of the buffer.
+    fn wrap_pos(&self, pos: usize) -> usize {
+        if pos >= self.data.len() {
+            pos - self.data.len()
+        }
+        else {
+            pos
+        }
+    }
+}
+
+impl From<Box<[u8]>> for RingBuf {
+    /// Creates a ring buffer with the given slice as backing buffer.
+    /// Note that the ring buffer capacity will be 1 byte less then the length of the slice,
+    /// see [`RingBuf::capacity_of`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice is empty, as it can't even hold a ring buffer with capacity 0.
+    /// Use [`RingBuf::try_from_boxed_slice`] to handle that case without panicking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0u8; 5].into_boxed_slice().into();
+    /// assert_eq!(rb.capacity(), 4);
+    /// ```
+    fn from(s: Box<[u8]>) -> Self {
+        assert!(!s.is_empty(), "backing buffer must not be empty");
+
+        RingBuf {
+            data: s,
+            read_pos: 0,
+            write_pos: 0,
+            policy: FullPolicy::default(),
+            read_bit_pos: 0,
+            write_bit_buf: 0,
+            write_bit_len: 0,
+            consumed: 0,
+            total_read: 0,
+            high_water_mark: 0,
+            panic_on_truncate: false,
+            zero_on_clear: false,
+            reset_on_empty: false,
+            growth_factor: 2.0,
+            compact_threshold: 0.25,
+            event_handler: None
+        }
+    }
+}
+
+impl From<Vec<u8>> for RingBuf {
+    /// Creates a ring buffer containing the bytes of the given vector.
+    ///
+    /// The capacity and length of the ring buffer will be equal to the length of the vector,
+    /// **i.e. the ring buffer will be full**: nothing can be written to it until some bytes are read.
+    /// Like with [`RingBuf::with_capacity`], `capacity()` is the number of usable bytes
+    /// and the underlying buffer is one byte longer, which this conversion takes care of
+    /// by appending a byte to the vector. Use [`RingBuf::reserve`] or [`RingBuf::with_capacity`]
+    /// and `write` if you need free space after the initial contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+    /// assert_eq!(rb.capacity(), 5);
+    /// assert_eq!(rb.len(), 5);
+    /// assert!(rb.is_full());
+    /// assert_eq!(rb.write(&[5]).unwrap(), 0);
+    /// ```
+    fn from(mut s: Vec<u8>) -> Self {
+        s.push(0);
+        let write_pos = s.len() - 1;
+        let mut rb: RingBuf = s.into_boxed_slice().into();
+        rb.write_pos = write_pos;
+        rb.update_high_water_mark();
+        rb
+    }
+}
+
+impl From<&[u8]> for RingBuf {
+    /// Creates a ring buffer containing a copy of the given slice.
+    /// Like with `From<Vec<u8>>`, the capacity and length of the ring buffer will be equal
+    /// to the length of the slice, i.e. the ring buffer will be full.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb = RingBuf::from(&b"hello"[..]);
+    /// assert_eq!(rb.len(), 5);
+    /// assert!(rb.is_full());
+    ///
+    /// let mut buf = [0u8; 5];
+    /// rb.peek(&mut buf).unwrap();
+    /// assert_eq!(&buf, b"hello");
+    /// ```
+    fn from(s: &[u8]) -> Self {
+        let mut vec = Vec::with_capacity(RingBuf::backing_len(s.len()));
+        vec.extend_from_slice(s);
+        vec.into()
+    }
+}
+
+impl Drop for RingBuf {
+    fn drop(&mut self) {
+        if self.zero_on_clear {
+            self.data.fill(0);
+        }
+    }
+}
+
+impl PartialEq for RingBuf {
+    /// Compares the contents of the ring buffers, regardless of their capacity or
+    /// where the contents are located in the underlying buffer,
+    /// see also [`RingBuf::structural_eq`].
+    fn eq(&self, other: &RingBuf) -> bool {
+        check_valid!(self);
+
+        self.len() == other.len() && self.peek_iter().eq(other.peek_iter())
+    }
+}
+
+impl Eq for RingBuf {}
+
+impl Read for RingBuf {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        check_valid!(self);
+
+        let bytes_read = self.peek(buf)?;
+        self.advance_read_pos(bytes_read)?;
+        Ok(bytes_read)
+    }
+}
+
+impl Write for RingBuf {
+    /// Writes `buf` to the ring buffer according to its [`FullPolicy`].
+    ///
+    /// With `FullPolicy::Reject` only the first `remaining()` bytes are written
+    /// and their count is returned. With `FullPolicy::OverwriteOldest` and `FullPolicy::Grow`
+    /// all of `buf` is always accepted, so `buf.len()` is returned.
+    ///
+    /// This means `write_all` fails with `ErrorKind::WriteZero` under `FullPolicy::Reject`
+    /// once the ring buffer is full, while under the other policies it always succeeds.
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        Ok(self.write_reporting(buf).0)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        check_valid!(self);
+
+        Ok(())
+    }
+
+    /// Writes formatted text to the ring buffer, copying each formatted piece
+    /// with a single call to the same code as `write`, instead of looping like `write_all`.
+    ///
+    /// Each piece follows the [`FullPolicy`] and reports events like `write` does.
+    /// Under `FullPolicy::Reject` this fails with `ErrorKind::WriteZero` as soon as
+    /// a piece doesn't fit, keeping the text that was written up to that point.
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> Result<()> {
+        check_valid!(self);
+
+        let mut adapter = FmtAdapter {
+            rb: self,
+            error: None
+        };
+        match args.as_str() {
+            Some(s) => fmt::Write::write_str(&mut adapter, s),
+            None => fmt::write(&mut adapter, args)
+        }
+        .map_err(|_| adapter.error.take().unwrap_or_else(|| Error::other("Formatter error.")))
+    }
+}
+
+/// Forwards formatted text to a [`RingBuf`], keeping the I/O error that stopped formatting.
+struct FmtAdapter<'a> {
+    rb: &'a mut RingBuf,
+    error: Option<Error>
+}
+
+impl fmt::Write for FmtAdapter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.rb.write_reporting(s.as_bytes()).0 < s.len() {
+            self.error = Some(RingError::Full.into());
+            return Err(fmt::Error);
+        }
+
+        Ok(())
+    }
+}
+
+/// A builder for configuring and constructing a [`RingBuf`].
+///
+/// # Examples
+///
+/// ```
+/// use bytebufrs::{FullPolicy, RingBufBuilder};
+///
+/// let rb = RingBufBuilder::new()
+///     .capacity(100)
+///     .power_of_two(true)
+///     .policy(FullPolicy::OverwriteOldest)
+///     .build();
+///
+/// assert_eq!(rb.capacity(), 128);
+/// assert_eq!(rb.policy(), FullPolicy::OverwriteOldest);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RingBufBuilder {
+    capacity: usize,
+    policy: FullPolicy,
+    power_of_two: bool,
+    fill: Option<u8>
+}
+
+impl RingBufBuilder {
+    /// Constructs a new builder for an empty ring buffer with capacity 0
+    /// and the default [`FullPolicy`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the capacity of the ring buffer.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the [`FullPolicy`] of the ring buffer.
+    pub fn policy(mut self, policy: FullPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Rounds the capacity up to the next power of two when enabled,
+    /// so a capacity of 0 becomes 1.
+    pub fn power_of_two(mut self, power_of_two: bool) -> Self {
+        self.power_of_two = power_of_two;
+        self
+    }
+
+    /// Fills the ring buffer with `byte`, making it full.
+    pub fn fill(mut self, byte: u8) -> Self {
+        self.fill = Some(byte);
+        self
+    }
+
+    /// Constructs the configured ring buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if rounding the capacity up to a power of two,
+    /// or the length of the underlying buffer, overflows `usize`.
+    pub fn build(self) -> RingBuf {
+        let capacity = if self.power_of_two {
+            self.capacity.checked_next_power_of_two().expect("capacity overflow")
+        }
+        else {
+            self.capacity
+        };
+
+        let mut rb = match self.fill {
+            Some(byte) => RingBuf::filled(capacity, byte),
+            None => RingBuf::with_capacity(capacity)
+        };
+        rb.policy = self.policy;
+        rb
+    }
+}
+
+/// Reads the contents of the ring buffer through the `bytes` crate's `Buf` trait.
+///
+/// Note that `Buf::remaining` returns the number of bytes that can be read, i.e. `len()`,
+/// while the inherent `RingBuf::remaining` returns the free space.
+///
+/// `bytes::BufMut` is implemented as well with the `bytes-mut` feature.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Buf;
+///
+/// use bytebufrs::RingBuf;
+///
+/// let mut rb: RingBuf = vec![0, 0, 1, 2, 3].into();
+/// assert_eq!(rb.get_u16(), 0);
+/// assert_eq!(rb.get_u8(), 1);
+/// assert_eq!(Buf::remaining(&rb), 2);
+/// ```
+#[cfg(feature = "bytes")]
+impl bytes::Buf for RingBuf {
+    fn remaining(&self) -> usize {
+        self.len()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.as_slices().0
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.advance_read_pos(cnt).expect("cannot advance past `remaining`");
+    }
+}
+
+#[cfg(feature = "bytes-mut")]
+#[allow(unsafe_code)]
+mod bytes_mut {
+    use bytes::buf::UninitSlice;
+
+    use crate::RingBuf;
+
+    /// Writes to the free space of the ring buffer through the `bytes` crate's `BufMut` trait.
+    ///
+    /// `BufMut::remaining_mut` returns the free space, i.e. `remaining()`, regardless of
+    /// the [`FullPolicy`](crate::FullPolicy), so putting more bytes than are free panics.
+    /// `BufMut::chunk_mut` returns the contiguous free space after the write position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::BufMut;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb = RingBuf::with_capacity(4);
+    /// rb.put_u16(0x0102);
+    /// rb.put_u8(3);
+    /// assert_eq!(rb.remaining_mut(), 1);
+    /// assert!(rb.eq_slice(&[1, 2, 3]));
+    /// ```
+    // SAFETY: `chunk_mut` returns the contiguous free space, which is initialized,
+    // and `advance_mut` panics instead of moving the write position past the free space.
+    unsafe impl bytes::BufMut for RingBuf {
+        fn remaining_mut(&self) -> usize {
+            self.remaining()
+        }
+
+        unsafe fn advance_mut(&mut self, cnt: usize) {
+            self.commit_write(cnt).expect("cannot advance past `remaining_mut`");
+        }
+
+        fn chunk_mut(&mut self) -> &mut UninitSlice {
+            UninitSlice::new(self.contiguous_free_mut())
+        }
+    }
+}
+
+/// An iterator over the bytes of a [`RingBuf`] that doesn't consume them.
+///
+/// This struct is created by [`RingBuf::peek_iter`].
+pub struct PeekIter<'a> {
+    rb: &'a RingBuf,
+    pos: usize,
+    len: usize
+}
+
+impl Iterator for PeekIter<'_> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let byte = self.rb.data[self.pos];
+        self.pos = self.rb.wrap_pos(self.pos + 1);
+        self.len -= 1;
+        Some(byte)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl ExactSizeIterator for PeekIter<'_> {}
+
+/// An iterator over the `(outgoing, incoming)` byte pairs of a window sliding over a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::slide`].
+pub struct Slide<'a> {
+    incoming: PeekIter<'a>,
+    outgoing: PeekIter<'a>,
+    window: usize,
+    seen: usize
+}
+
+impl Iterator for Slide<'_> {
+    type Item = (Option<u8>, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let incoming = self.incoming.next()?;
+        let outgoing = if self.seen >= self.window {
+            self.outgoing.next()
+        }
+        else {
+            None
+        };
+
+        self.seen += 1;
+        Some((outgoing, incoming))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.incoming.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Slide<'_> {}
+
+/// A cursor over the contents of a [`RingBuf`] that doesn't consume them.
+///
+/// This struct is created by [`RingBuf::peek_cursor`].
+pub struct PeekCursor<'a> {
+    rb: &'a RingBuf,
+    offset: usize
+}
+
+impl PeekCursor<'_> {
+    /// Returns the next byte without advancing the cursor,
+    /// or `None` if the cursor is at the end of the contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![7].into();
+    /// let cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.peek_u8(), Some(7));
+    /// assert_eq!(cursor.remaining(), 1);
+    /// ```
+    pub fn peek_u8(&self) -> Option<u8> {
+        if self.remaining() == 0 {
+            return None;
+        }
+
+        Some(self.rb.data[self.rb.wrap_pos(self.rb.read_pos + self.offset)])
+    }
+
+    /// Reads the next byte, or returns `None` without advancing the cursor
+    /// if it is at the end of the contents.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![7].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.read_u8(), Some(7));
+    /// assert_eq!(cursor.read_u8(), None);
+    /// ```
+    pub fn read_u8(&mut self) -> Option<u8> {
+        let byte = self.peek_u8()?;
+        self.offset += 1;
+        Some(byte)
+    }
+
+    /// Reads the next two bytes as a big endian `u16`, or returns `None` without advancing
+    /// the cursor if fewer than two bytes remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0x12, 0x34, 0x56].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.read_u16_be(), Some(0x1234));
+    /// assert_eq!(cursor.read_u16_be(), None);
+    /// assert_eq!(cursor.remaining(), 1);
+    /// ```
+    pub fn read_u16_be(&mut self) -> Option<u16> {
+        let mut bytes = [0u8; 2];
+        if self.remaining() < bytes.len() {
+            return None;
+        }
+
+        self.rb.copy_at(self.offset, &mut bytes);
+        self.offset += bytes.len();
+        Some(u16::from_be_bytes(bytes))
+    }
+
+    /// Advances the cursor by `count` bytes, or returns `None` without advancing it
+    /// if fewer than `count` bytes remain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// assert_eq!(cursor.skip(2), Some(()));
+    /// assert_eq!(cursor.skip(2), None);
+    /// assert_eq!(cursor.read_u8(), Some(2));
+    /// ```
+    pub fn skip(&mut self, count: usize) -> Option<()> {
+        if self.remaining() < count {
+            return None;
+        }
+
+        self.offset += count;
+        Some(())
+    }
+
+    /// Returns the number of bytes after the cursor.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// cursor.read_u8();
+    /// assert_eq!(cursor.remaining(), 2);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.rb.len() - self.offset
+    }
+
+    /// Ends the borrow of the ring buffer, returning how far the cursor advanced
+    /// so it can be passed to [`RingBuf::commit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// cursor.read_u8();
+    /// let mark = cursor.into_mark();
+    /// assert_eq!(mark.offset(), 1);
+    /// rb.commit(mark).unwrap();
+    /// assert!(rb.eq_slice(&[1, 2]));
+    /// ```
+    pub fn into_mark(self) -> PeekMark {
+        PeekMark {
+            offset: self.offset
+        }
+    }
+}
+
+/// How far a [`PeekCursor`] advanced, to be consumed with [`RingBuf::commit`].
+///
+/// This struct is created by [`PeekCursor::into_mark`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PeekMark {
+    offset: usize
+}
+
+impl PeekMark {
+    /// Returns the number of bytes the cursor advanced over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let rb: RingBuf = vec![0, 1, 2].into();
+    /// let mut cursor = rb.peek_cursor();
+    /// cursor.skip(3);
+    /// assert_eq!(cursor.into_mark().offset(), 3);
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// The state of a [`RingBuf`], captured by [`RingBuf::snapshot`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    capacity: usize,
+    read_pos: usize,
+    bytes: Vec<u8>
+}
+
+/// A buffered writer using a [`RingBuf`] as its buffer.
+///
+/// This struct is created by [`RingBuf::into_buffered_writer`].
+pub struct BufWriterRing<W: Write> {
+    ring: RingBuf,
+    sink: W
+}
+
+impl<W: Write> BufWriterRing<W> {
+    /// Returns a reference to the sink.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+    /// assert!(writer.get_ref().is_empty());
+    /// ```
+    pub fn get_ref(&self) -> &W {
+        &self.sink
+    }
+
+    /// Returns a mutable reference to the sink.
+    /// Writing to it directly bypasses the bytes still in the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+    /// writer.get_mut().push(0);
+    /// assert_eq!(writer.get_ref(), &[0]);
+    /// ```
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.sink
+    }
+
+    /// Returns the ring buffer holding the bytes that haven't been written to the sink yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+    /// writer.write_all(b"ab").unwrap();
+    /// assert!(writer.buffer().eq_slice(b"ab"));
+    /// ```
+    pub fn buffer(&self) -> &RingBuf {
+        &self.ring
+    }
+
+    /// Writes the bytes in the ring buffer to the sink and returns the sink.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Write;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+    /// writer.write_all(b"ab").unwrap();
+    /// assert_eq!(writer.into_inner().unwrap(), b"ab");
+    /// ```
+    pub fn into_inner(mut self) -> Result<W> {
+        self.flush_ring()?;
+        Ok(self.sink)
+    }
+
+    /// Writes the bytes in the ring buffer to the sink, without flushing the sink.
+    fn flush_ring(&mut self) -> Result<()> {
+        while !self.ring.is_empty() {
+            match self.sink.write(self.ring.as_slices().0) {
+                Ok(0) => return Err(Error::new(ErrorKind::WriteZero,
+                    "Failed to write the buffered data.")),
+                Ok(written) => self.ring.consume(written),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {},
+                Err(err) => return Err(err)
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for BufWriterRing<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        if buf.len() > self.ring.remaining() {
+            self.flush_ring()?;
+        }
+
+        if buf.len() >= self.ring.capacity() {
+            self.sink.write(buf)
+        }
+        else {
+            Ok(self.ring.write_truncating(buf))
+        }
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.flush_ring()?;
+        self.sink.flush()
+    }
+}
+
+/// A reader over two [`RingBuf`]s, reading all bytes of the first before those of the second.
+///
+/// This struct is created by [`RingBuf::chain`].
+pub struct Chain {
+    first: RingBuf,
+    second: RingBuf
+}
+
+impl Chain {
+    /// Returns the total number of bytes left in both ring buffers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let chain = RingBuf::from(vec![0]).chain(vec![1, 2].into());
+    /// assert_eq!(chain.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.first.len() + self.second.len()
+    }
+
+    /// Returns `true` if both ring buffers are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let chain = RingBuf::with_capacity(2).chain(RingBuf::with_capacity(2));
+    /// assert!(chain.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.first.is_empty() && self.second.is_empty()
+    }
+
+    /// Returns the two ring buffers, with whatever bytes are left in them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut chain = RingBuf::from(vec![0]).chain(vec![1, 2].into());
+    /// chain.read_exact(&mut [0u8; 2]).unwrap();
+    ///
+    /// let (first, second) = chain.into_inner();
+    /// assert!(first.is_empty());
+    /// assert!(second.eq_slice(&[2]));
+    /// ```
+    pub fn into_inner(self) -> (RingBuf, RingBuf) {
+        (self.first, self.second)
+    }
+}
+
+impl Read for Chain {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let count = self.first.read(buf)?;
+        Ok(count + self.second.read(&mut buf[count..])?)
+    }
+}
+
+/// A reader reading at most a limited number of bytes from a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::take`].
+pub struct Take {
+    inner: RingBuf,
+    limit: u64
+}
+
+impl Take {
+    /// Returns the number of bytes that can still be read before signaling end of file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let mut take = RingBuf::from(vec![0, 1, 2]).take(2);
+    /// assert_eq!(take.limit(), 2);
+    ///
+    /// take.read_exact(&mut [0u8; 1]).unwrap();
+    /// assert_eq!(take.limit(), 1);
+    /// ```
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns a reference to the ring buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let take = RingBuf::from(vec![0, 1, 2]).take(2);
+    /// assert_eq!(take.get_ref().len(), 3);
+    /// ```
+    pub fn get_ref(&self) -> &RingBuf {
+        &self.inner
+    }
+
+    /// Returns the ring buffer, with whatever bytes are left in it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytebufrs::RingBuf;
+    ///
+    /// let take = RingBuf::from(vec![0, 1, 2]).take(2);
+    /// assert_eq!(take.into_inner().len(), 3);
+    /// ```
+    pub fn into_inner(self) -> RingBuf {
+        self.inner
+    }
+}
+
+impl Read for Take {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let max = usize::try_from(self.limit).map_or(buf.len(), |limit| min(limit, buf.len()));
+        let count = self.inner.read(&mut buf[..max])?;
+        self.limit -= count as u64;
+        Ok(count)
+    }
+}
+
+/// A non-consuming reader over the contents of a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::reader_view`].
+pub struct ReaderView<'a> {
+    rb: &'a RingBuf,
+    offset: usize
+}
+
+impl Read for ReaderView<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let count = min(self.rb.len() - self.offset, buf.len());
+        self.rb.copy_at(self.offset, &mut buf[..count]);
+        self.offset += count;
+        Ok(count)
+    }
+}
+
+/// An iterator over overlapping windows of the contents of a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::windows`].
+pub struct Windows<'a> {
+    rb: &'a RingBuf,
+    size: usize,
+    offset: usize
+}
+
+impl Iterator for Windows<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset + self.size > self.rb.len() {
+            return None;
+        }
+
+        let mut window = vec![0; self.size];
+        self.rb.copy_at(self.offset, &mut window);
+        self.offset += 1;
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.rb.len() + 1).saturating_sub(self.offset + self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Windows<'_> {}
+
+/// An iterator over fixed-size blocks of the contents of a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::peek_blocks`].
+pub struct Blocks<'a, const N: usize> {
+    rb: &'a RingBuf,
+    offset: usize
+}
+
+impl<const N: usize> Iterator for Blocks<'_, N> {
+    type Item = [u8; N];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset + N > self.rb.len() {
+            return None;
+        }
+
+        let mut block = [0; N];
+        self.rb.copy_at(self.offset, &mut block);
+        self.offset += N;
+        Some(block)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.rb.len() - self.offset) / N;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for Blocks<'_, N> {}
+
+/// An iterator over the delimited segments of the contents of a [`RingBuf`].
+///
+/// This struct is created by [`RingBuf::split`].
+pub struct Split<'a> {
+    rb: &'a RingBuf,
+    delim: u8,
+    offset: usize,
+    finished: bool
+}
+
+impl Iterator for Split<'_> {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let end = match self.rb.find_byte(self.delim, self.offset) {
+            Some(end) => end,
+            None => {
+                self.finished = true;
+                self.rb.len()
+            }
+        };
+
+        let mut segment = vec![0; end - self.offset];
+        self.rb.copy_at(self.offset, &mut segment);
+        self.offset = end + 1;
+        Some(segment)
+    }
+}
+
+/// An iterator over the complete lines in a [`RingBuf`], consuming them.
+///
+/// This struct is created by [`RingBuf::lines`].
+pub struct Lines<'a> {
+    rb: &'a mut RingBuf
+}
+
+impl Iterator for Lines<'_> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = self.rb.find_byte(b'\n', 0)? + 1;
+        let mut bytes = vec![0; count];
+        self.rb.copy_at(0, &mut bytes);
+        self.rb.consume(count);
+        Some(String::from_utf8(bytes)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "Stream did not contain valid UTF-8.")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::io::{ErrorKind, IoSlice, Read, Write};
+    use std::mem::MaybeUninit;
+
+    use crate::{BufEvent, FullPolicy, RingBuf, RingBufBuilder, RingError};
+
+    /// Returns an empty ring buffer with both positions at `offset`, by writing and reading
+    /// `offset` filler bytes, so the bytes written next wrap around after `capacity - offset`.
+    fn with_offset(capacity: usize, offset: usize) -> RingBuf {
+        let mut rb = RingBuf::with_capacity(capacity);
+        rb.write_all(&vec![0; offset]).unwrap();
+        rb.advance_read_pos(offset).unwrap();
+        rb
+    }
+
+    /// Returns the read and write offsets of `rb` in the underlying buffer.
+    fn offsets(rb: &RingBuf) -> (usize, usize) {
+        (rb.read_offset(), rb.write_offset())
+    }
+
+    #[test]
+    fn ringbuf_with_capacity() {
+        let rb = RingBuf::with_capacity(4);
+
+        assert_eq!(rb.capacity(), 4);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_from_vec() {
+        let mut rb: RingBuf = vec![5, 4, 3, 2, 1].into();
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        let mut buf = [0u8; 10];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [5, 4, 3, 2, 1, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        buf = [0u8; 10];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [5, 4, 3, 2, 1, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+
+        buf = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(rb.write(&buf).unwrap(), 5);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        buf = [0u8; 10];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_wrapped_read_write() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+
+        let buf = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        assert_eq!(rb.write(&buf).unwrap(), 5);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        let mut buf = [0u8; 3];
+
+        assert_eq!(rb.peek(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [0, 1, 2]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        buf = [0u8; 3];
+        assert_eq!(rb.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [0, 1, 2]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 2);
+        assert!(!rb.is_empty());
+
+        let mut buf = [9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
+        assert_eq!(rb.write(&buf).unwrap(), 3);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        buf = [0u8; 10];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [3, 4, 9, 8, 7, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        buf = [0u8; 10];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [3, 4, 9, 8, 7, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_clear() {
+        let mut rb: RingBuf = vec![5, 4, 3, 2, 1].into();
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 5);
+        assert!(!rb.is_empty());
+
+        rb.clear();
+
+        assert_eq!(rb.capacity(), 5);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_peek_read_empty() {
+        let mut rb = RingBuf::with_capacity(10);
+
+        let mut buf = [0u8; 10];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 0);
+        assert_eq!(rb.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn ringbuf_peek_read_0_len_buf() {
+        let mut rb: RingBuf = vec![0, 1, 2].into();
+
+        let mut buf = [0u8; 0];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 0);
+        assert_eq!(rb.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn ringbuf_read_write_larger_then_capacity() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]).unwrap(), 5);
+
+        let mut buf = [0u8; 10];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [1, 2, 3, 4, 5, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.write(&[6, 7, 8, 9, 10, 11, 12, 13, 14, 15]).unwrap(), 5);
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [6, 7, 8, 9, 10, 0, 0, 0, 0, 0]);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_read_write_buf_end() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[1]).unwrap(), 1);
+
+        let mut buf = [0u8; 10];
+        assert_eq!(rb.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]);
+        assert_eq!(rb.read_pos, 0);
+        assert_eq!(rb.write_pos, 0);
+        assert_eq!(rb.len(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_eq_slice() {
+        let rb: RingBuf = vec![0, 1, 2, 3, 4].into();
+
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+        assert!(!rb.eq_slice(&[0, 1, 2, 3, 5]));
+        assert!(!rb.eq_slice(&[0, 1, 2, 3]));
+        assert!(!rb.eq_slice(&[0, 1, 2, 3, 4, 5]));
+
+        let rb = RingBuf::with_capacity(5);
+        assert!(rb.eq_slice(&[]));
+        assert!(!rb.eq_slice(&[0]));
+    }
+
+    #[test]
+    fn ringbuf_eq_slice_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+        assert!(!rb.eq_slice(&[3, 4, 5, 6, 8]));
+        assert!(!rb.eq_slice(&[3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn ringbuf_starts_with_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        assert!(rb.starts_with(&[]));
+        assert!(rb.starts_with(&[3]));
+        assert!(rb.starts_with(&[3, 4, 5]));
+        assert!(rb.starts_with(&[3, 4, 5, 6, 7]));
+        assert!(!rb.starts_with(&[3, 4, 6]));
+        assert!(!rb.starts_with(&[3, 4, 5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn ringbuf_windows_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let expected = [3, 4, 5, 6, 7];
+        for size in 1..=5 {
+            let windows: Vec<Vec<u8>> = rb.windows(size).collect();
+            let contiguous: Vec<Vec<u8>> = expected.windows(size).map(|w| w.to_vec()).collect();
+            assert_eq!(windows, contiguous);
+            assert_eq!(rb.windows(size).len(), contiguous.len());
+        }
+
+        assert_eq!(rb.windows(6).next(), None);
+        assert_eq!(rb.windows(6).len(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_windows_0_size() {
+        let rb = RingBuf::with_capacity(5);
+        rb.windows(0);
+    }
+
+    #[test]
+    fn ringbuf_available_contiguous() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.available_contiguous_read(), 0);
+        assert_eq!(rb.available_contiguous_write(), 5);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        assert_eq!(rb.available_contiguous_read(), 4);
+        assert_eq!(rb.available_contiguous_write(), 1);
+
+        // Free space is fragmented: 2 bytes at the end, 1 at the start.
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.available_contiguous_read(), 2);
+        assert_eq!(rb.available_contiguous_write(), 2);
+
+        // Readable bytes are fragmented: 2 bytes at the end, 1 at the start.
+        assert_eq!(rb.write(&[4, 5, 6]).unwrap(), 3);
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.len(), 3);
+        assert_eq!(rb.available_contiguous_read(), 2);
+        assert_eq!(rb.available_contiguous_write(), 2);
+
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.available_contiguous_read(), 1);
+        assert_eq!(rb.available_contiguous_write(), 4);
+    }
+
+    #[test]
+    fn ringbuf_insert_front() {
+        let mut rb: RingBuf = vec![0u8; 2].into_boxed_slice().into();
+        assert_eq!(rb.capacity(), 1);
+        assert!(rb.insert_front(&[0, 1]).is_err());
+        assert!(rb.is_empty());
+
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.write(&[3, 4]).unwrap(), 2);
+        rb.insert_front(&[0, 1, 2]).unwrap();
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+
+        assert!(rb.insert_front(&[0]).is_err());
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn ringbuf_insert_front_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[0, 1, 2]).unwrap(), 3);
+        rb.advance_read_pos(1).unwrap();
+        rb.insert_front(&[7, 8, 9]).unwrap();
+        assert_eq!(rb.read_pos, 4);
+        assert!(rb.eq_slice(&[7, 8, 9, 1, 2]));
+
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [7, 8, 9, 1, 2]);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_swap_backing() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let old = rb.swap_backing(vec![0u8; 9].into_boxed_slice());
+        assert_eq!(old.len(), 6);
+        assert_eq!(rb.capacity(), 8);
+        assert_eq!(rb.len(), 5);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        assert_eq!(rb.write(&[8, 9, 10, 11]).unwrap(), 3);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7, 8, 9, 10]));
+
+        let old = rb.swap_backing(old);
+        assert_eq!(old.len(), 9);
+        assert_eq!(rb.capacity(), 5);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_swap_backing_empty() {
+        let mut rb = RingBuf::with_capacity(5);
+        rb.swap_backing(Vec::new().into_boxed_slice());
+    }
+
+    #[test]
+    fn ringbuf_offsets() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.logical_positions(), (0, 0));
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        assert_eq!(rb.read_offset(), 0);
+        assert_eq!(rb.write_offset(), 4);
+
+        let mut buf = [0u8; 3];
+        assert_eq!(rb.read(&mut buf).unwrap(), 3);
+        assert_eq!(rb.read_offset(), 3);
+        assert_eq!(rb.write_offset(), 4);
+
+        assert_eq!(rb.write(&[4, 5, 6]).unwrap(), 3);
+        assert_eq!((rb.read_offset(), rb.write_offset()), (3, 1));
+        // The logical positions don't wrap around the end of the underlying buffer.
+        assert_eq!(rb.logical_positions(), (3, 7));
+
+        rb.clear();
+        assert_eq!((rb.read_offset(), rb.write_offset()), (0, 0));
+        assert_eq!(rb.logical_positions(), (7, 7));
+    }
+
+    #[test]
+    fn ringbuf_read_line_wrapped() {
+        let mut rb = with_offset(8, 7);
+        assert_eq!(rb.write(b"ab\ncd\nef").unwrap(), 8);
+
+        let mut line = String::new();
+        assert_eq!(rb.read_line(&mut line).unwrap(), 3);
+        assert_eq!(line, "ab\n");
+
+        line.clear();
+        assert_eq!(rb.read_line(&mut line).unwrap(), 3);
+        assert_eq!(line, "cd\n");
+        assert_eq!(rb.len(), 2);
+    }
+
+    #[test]
+    fn ringbuf_read_line_no_newline() {
+        let mut rb: RingBuf = b"abc".to_vec().into();
+
+        let mut line = String::new();
+        assert_eq!(rb.read_line(&mut line).unwrap(), 3);
+        assert_eq!(line, "abc");
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.read_line(&mut line).unwrap(), 0);
+        assert_eq!(line, "abc");
+    }
+
+    #[test]
+    fn ringbuf_read_line_invalid_utf8() {
+        let mut rb: RingBuf = vec![b'a', 0xff, b'\n', b'b'].into();
+
+        let mut line = String::from("x");
+        let err = rb.read_line(&mut line).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(line, "x");
+        assert_eq!(rb.len(), 4);
+    }
+
+    #[test]
+    fn ringbuf_backing_len_capacity_of() {
+        for &capacity in &[0, 1, 5, 4096, usize::MAX - 1] {
+            assert_eq!(RingBuf::backing_len(capacity), capacity + 1);
+            assert_eq!(RingBuf::capacity_of(RingBuf::backing_len(capacity)), capacity);
+        }
+
+        assert_eq!(RingBuf::capacity_of(0), 0);
+        assert_eq!(RingBuf::capacity_of(1), 0);
+
+        let rb = RingBuf::with_capacity(7);
+        assert_eq!(rb.data.len(), RingBuf::backing_len(7));
+        assert_eq!(RingBuf::capacity_of(rb.data.len()), rb.capacity());
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_backing_len_overflow() {
+        RingBuf::backing_len(usize::MAX);
+    }
+
+    #[test]
+    fn ringbuf_generic_io_by_reference() {
+        fn write_to(mut w: impl Write, buf: &[u8]) -> usize {
+            w.write(buf).unwrap()
+        }
+
+        fn read_from(mut r: impl Read, buf: &mut [u8]) -> usize {
+            r.read(buf).unwrap()
+        }
+
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(write_to(&mut rb, &[0, 1, 2]), 3);
+        assert_eq!(write_to(&mut rb, &[3, 4, 5]), 2);
+        assert_eq!(rb.len(), 5);
+
+        let mut buf = [0u8; 3];
+        assert_eq!(read_from(&mut rb, &mut buf), 3);
+        assert_eq!(buf, [0, 1, 2]);
+        assert_eq!(rb.len(), 2);
+
+        let mut rb = Box::new(rb);
+        assert_eq!(write_to(&mut rb, &[5, 6]), 2);
+        assert_eq!(read_from(&mut rb, &mut buf), 3);
+        assert_eq!(buf, [3, 4, 5]);
+        assert_eq!(write_to(rb, &[7]), 1);
+    }
+
+    #[test]
+    fn ringbuf_drain_at_most_to_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut sink = Vec::new();
+        assert_eq!(rb.drain_at_most_to(&mut sink, 3).unwrap(), 3);
+        assert_eq!(sink, [3, 4, 5]);
+        assert!(rb.eq_slice(&[6, 7]));
+
+        assert_eq!(rb.drain_at_most_to(&mut sink, 10).unwrap(), 2);
+        assert_eq!(sink, [3, 4, 5, 6, 7]);
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.drain_at_most_to(&mut sink, 10).unwrap(), 0);
+    }
+
+    #[test]
+    fn ringbuf_drain_at_most_to_short_write() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut buf = [0u8; 4];
+        let mut sink = &mut buf[..];
+        assert_eq!(rb.drain_at_most_to(&mut sink, 5).unwrap(), 4);
+        assert_eq!(buf, [3, 4, 5, 6]);
+        assert!(rb.eq_slice(&[7]));
+    }
+
+    #[test]
+    fn ringbuf_drain_at_most_to_second_write_fails() {
+        /// Accepts everything on the first call and fails on every later call.
+        struct FailingWriter {
+            calls: usize,
+            written: Vec<u8>
+        }
+
+        impl Write for FailingWriter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.calls += 1;
+                if self.calls > 1 {
+                    return Err(std::io::Error::new(ErrorKind::BrokenPipe, "Broken pipe."));
+                }
+
+                self.written.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut w = FailingWriter {
+            calls: 0,
+            written: Vec::new()
+        };
+        assert_eq!(rb.drain_at_most_to(&mut w, 5).unwrap(), 3);
+        assert_eq!(w.written, [3, 4, 5]);
+        assert!(rb.eq_slice(&[6, 7]));
+
+        // Without bytes accepted by a first call, the error is returned.
+        let err = rb.drain_at_most_to(&mut w, 5).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BrokenPipe);
+        assert!(rb.eq_slice(&[6, 7]));
+    }
+
+    #[test]
+    fn ringbuf_clear_and_resize() {
+        let mut rb: RingBuf = vec![5, 4, 3, 2, 1].into();
+
+        rb.clear_and_resize(3);
+        assert_eq!(rb.capacity(), 3);
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 3);
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [0, 1, 2, 0, 0]);
+
+        assert_eq!(rb.write(&[4]).unwrap(), 1);
+        let ptr = rb.data.as_ptr();
+        rb.clear_and_resize(3);
+        assert_eq!(rb.capacity(), 3);
+        assert!(rb.is_empty());
+        assert_eq!(rb.data.as_ptr(), ptr);
+
+        rb.clear_and_resize(8);
+        assert_eq!(rb.capacity(), 8);
+        assert!(rb.is_empty());
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4, 5, 6, 7, 8]).unwrap(), 8);
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn ringbuf_write_with() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        rb.advance_read_pos(3).unwrap();
+
+        // Only the free space up to the end of the underlying buffer is handed out.
+        assert_eq!(rb.write_with(|buf| {
+            assert_eq!(buf.len(), 2);
+            buf.copy_from_slice(&[4, 5]);
+            2
+        }), 2);
+        assert_eq!(rb.write_pos, 0);
+
+        assert_eq!(rb.write_with(|buf| {
+            assert_eq!(buf.len(), 2);
+            buf[0] = 6;
+            1
+        }), 1);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_write_with_overreport() {
+        let mut rb = RingBuf::with_capacity(5);
+        rb.write_with(|buf| buf.len() + 1);
+    }
+
+    #[test]
+    fn ringbuf_write_with_min_compacts() {
+        let mut rb = RingBuf::with_capacity(6);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.available_contiguous_write(), 2);
+        assert_eq!(rb.remaining(), 4);
+
+        assert_eq!(rb.write_with_min(4, |buf| {
+            assert_eq!(buf.len(), 4);
+            buf.copy_from_slice(&[5, 6, 7, 8]);
+            4
+        }), 4);
+        assert_eq!(rb.read_pos, 0);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn ringbuf_write_with_min_not_enough_space() {
+        let mut rb = RingBuf::with_capacity(6);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+
+        assert_eq!(rb.write_with_min(5, |buf| {
+            assert_eq!(buf.len(), 2);
+            0
+        }), 0);
+        assert_eq!(rb.read_pos, 3);
+        assert!(rb.eq_slice(&[3, 4]));
+    }
+
+    #[test]
+    fn ringbuf_split_off_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        // Offset 4 is past the end of the underlying buffer.
+        let mut tail = rb.split_off(4);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+        assert!(tail.eq_slice(&[7]));
+        assert_eq!(tail.capacity(), 5);
+
+        assert_eq!(rb.write(&[8, 9]).unwrap(), 1);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 8]));
+        assert_eq!(tail.write(&[8, 9]).unwrap(), 2);
+        assert!(tail.eq_slice(&[7, 8, 9]));
+
+        let empty = rb.split_off(5);
+        assert!(empty.is_empty());
+        assert_eq!(rb.len(), 5);
+
+        let all = rb.split_off(0);
+        assert!(rb.is_empty());
+        assert!(all.eq_slice(&[3, 4, 5, 6, 8]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_split_off_past_len() {
+        let mut rb: RingBuf = vec![0, 1, 2].into();
+        rb.split_off(4);
+    }
+
+    #[test]
+    fn ringbuf_to_vec_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.to_vec(), []);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write(&[5, 6, 7]).unwrap(), 3);
+        assert_eq!(rb.to_vec(), [3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    #[cfg(feature = "crc32fast")]
+    fn ringbuf_checksum_crc32_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.checksum_crc32(), crc32fast::hash(&[]));
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        assert_eq!(rb.checksum_crc32(), crc32fast::hash(&rb.to_vec()));
+
+        for i in 5..20 {
+            rb.advance_read_pos(2).unwrap();
+            assert_eq!(rb.write(&[i, i + 1]).unwrap(), 2);
+            assert_eq!(rb.checksum_crc32(), crc32fast::hash(&rb.to_vec()));
+        }
+    }
+
+    #[test]
+    fn ringbuf_read_to_end_ring_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut out = Vec::new();
+        assert_eq!(rb.read_to_end_ring(&mut out), 5);
+        assert_eq!(out, [3, 4, 5, 6, 7]);
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.read_to_end_ring(&mut out), 0);
+        assert_eq!(out, [3, 4, 5, 6, 7]);
+
+        assert_eq!(rb.write(&[8, 9]).unwrap(), 2);
+        assert_eq!(rb.read_to_end_ring(&mut out), 2);
+        assert_eq!(out, [3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn ringbuf_policy_reject() {
+        let mut rb = RingBuf::with_policy(5, FullPolicy::Reject);
+
+        assert_eq!(rb.write(&[0, 1, 2]).unwrap(), 3);
+        assert_eq!(rb.write(&[3, 4, 5, 6]).unwrap(), 2);
+        assert_eq!(rb.write(&[7]).unwrap(), 0);
+        assert_eq!(rb.capacity(), 5);
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn ringbuf_policy_overwrite_oldest() {
+        let mut rb = RingBuf::with_policy(5, FullPolicy::OverwriteOldest);
+
+        assert_eq!(rb.write(&[0, 1, 2]).unwrap(), 3);
+        assert_eq!(rb.write(&[3, 4, 5, 6]).unwrap(), 4);
+        assert_eq!(rb.capacity(), 5);
+        assert!(rb.eq_slice(&[2, 3, 4, 5, 6]));
+
+        assert_eq!(rb.write(&[7]).unwrap(), 1);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        assert_eq!(rb.write(&[10, 11, 12, 13, 14, 15, 16]).unwrap(), 7);
+        assert_eq!(rb.capacity(), 5);
+        assert!(rb.eq_slice(&[12, 13, 14, 15, 16]));
+    }
+
+    #[test]
+    fn ringbuf_policy_grow() {
+        let mut rb = RingBuf::with_policy(5, FullPolicy::Grow);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write(&[4, 5, 6]).unwrap(), 3);
+
+        assert_eq!(rb.write(&[7, 8, 9]).unwrap(), 3);
+        assert_eq!(rb.capacity(), 10);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7, 8, 9]));
+
+        assert_eq!(rb.write(&[0; 20]).unwrap(), 20);
+        assert_eq!(rb.capacity(), 27);
+        assert_eq!(rb.len(), 27);
+    }
+
+    #[test]
+    fn ringbuf_peek_back_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        // The last 3 bytes span the end of the underlying buffer.
+        let mut buf = [0u8; 3];
+        assert_eq!(rb.peek_back(&mut buf), 3);
+        assert_eq!(buf, [5, 6, 7]);
+
+        let mut buf = [0u8; 2];
+        assert_eq!(rb.peek_back(&mut buf), 2);
+        assert_eq!(buf, [6, 7]);
+
+        let mut buf = [0u8; 7];
+        assert_eq!(rb.peek_back(&mut buf), 5);
+        assert_eq!(buf, [3, 4, 5, 6, 7, 0, 0]);
+        assert_eq!(rb.len(), 5);
+    }
+
+    #[test]
+    fn ringbuf_write_at_most() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        rb.advance_read_pos(3).unwrap();
+
+        assert_eq!(rb.write_at_most(&[4, 5, 6, 7], 3), 3);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+
+        assert_eq!(rb.write_at_most(&[7, 8], 5), 1);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        let mut rb = RingBuf::with_policy(5, FullPolicy::Grow);
+        assert_eq!(rb.write_at_most(&[0, 1, 2, 3, 4, 5, 6], 6), 5);
+        assert_eq!(rb.capacity(), 5);
+    }
+
+    #[test]
+    fn ringbuf_contiguous_copy_reuses_scratch() {
+        let mut scratch = Vec::with_capacity(16);
+        let ptr = scratch.as_ptr();
+
+        for shift in 0..6 {
+            let mut rb = RingBuf::with_capacity(5);
+            assert_eq!(rb.write(&[0; 5][..shift]).unwrap(), shift);
+            rb.advance_read_pos(shift).unwrap();
+            assert_eq!(rb.write(&[1, 2, 3, 4, 5]).unwrap(), 5);
+
+            assert_eq!(rb.contiguous_copy(&mut scratch), [1, 2, 3, 4, 5]);
+            assert_eq!(scratch.as_ptr(), ptr);
+        }
+    }
+
+    #[test]
+    fn ringbuf_read_bits_wrapped() {
+        let mut rb = with_offset(3, 2);
+        assert_eq!(rb.write(&[0b1010_1100, 0b1111_0000, 0b1100_0000]).unwrap(), 3);
+
+        assert_eq!(rb.read_bits(3).unwrap(), 0b101);
+        assert_eq!(rb.len(), 3);
+        // Spans two byte boundaries and the end of the underlying buffer.
+        assert_eq!(rb.read_bits(13).unwrap(), 0b0_1100_1111_0000);
+        assert_eq!(rb.len(), 1);
+        assert_eq!(rb.read_bits(3).unwrap(), 0b110);
+
+        let err = rb.read_bits(6).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(rb.read_bits(5).unwrap(), 0);
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.read_bits(0).unwrap(), 0);
+        assert_eq!(rb.read_bits(65).unwrap_err().kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn ringbuf_read_bits_after_rewind_write() {
+        let mut rb = RingBuf::with_capacity(4);
+        rb.write_all(&[0xff, 0xff]).unwrap();
+        assert_eq!(rb.read_bits(3).unwrap(), 0b111);
+
+        rb.rewind_write(2).unwrap();
+        let err = rb.read_bits(1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+
+        // The partially read byte is gone, so reading starts at the next byte.
+        rb.write_all(&[0b1000_0000]).unwrap();
+        assert_eq!(rb.read_bits(1).unwrap(), 1);
+    }
+
+    #[test]
+    fn ringbuf_write_bits_wrapped() {
+        let mut rb = with_offset(3, 3);
+
+        rb.write_bits(0b101, 3).unwrap();
+        rb.write_bits(0x1ABC, 13).unwrap();
+        rb.write_bits(0b11, 2).unwrap();
+        assert_eq!(rb.len(), 2);
+
+        let err = rb.write_bits(0, 15).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert_eq!(rb.len(), 2);
+
+        rb.flush_bits().unwrap();
+        assert_eq!(rb.len(), 3);
+        assert_eq!(rb.write_pos, 2);
+
+        assert_eq!(rb.read_bits(3).unwrap(), 0b101);
+        assert_eq!(rb.read_bits(13).unwrap(), 0x1ABC);
+        assert_eq!(rb.read_bits(2).unwrap(), 0b11);
+        rb.byte_align();
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_bits_mixed_with_bytes() {
+        let mut rb: RingBuf = vec![0b1110_0000, 1, 2].into();
+
+        assert_eq!(rb.read_bits(3).unwrap(), 0b111);
+
+        let mut buf = [0u8; 1];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 1);
+        assert_eq!(buf, [0b1110_0000]);
+
+        assert_eq!(rb.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf, [0b1110_0000]);
+        assert_eq!(rb.read_bits(8).unwrap(), 1);
+
+        rb.clear();
+        rb.write_bits(0b1, 1).unwrap();
+        rb.clear();
+        rb.flush_bits().unwrap();
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_try_from_boxed_slice() {
+        let rb = RingBuf::try_from_boxed_slice(vec![0u8; 1].into_boxed_slice()).unwrap();
+        assert_eq!(rb.capacity(), 0);
+        assert!(rb.is_empty());
+
+        let rb = RingBuf::try_from_boxed_slice(vec![0u8; 6].into_boxed_slice()).unwrap();
+        assert_eq!(rb.capacity(), 5);
+
+        let err = RingBuf::try_from_boxed_slice(Vec::new().into_boxed_slice()).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    #[should_panic(expected = "backing buffer must not be empty")]
+    fn ringbuf_from_empty_boxed_slice() {
+        let _: RingBuf = Vec::new().into_boxed_slice().into();
+    }
+
+    #[test]
+    fn ringbuf_rewind_read() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.rewind_read(), 0);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        let mut buf = [0u8; 3];
+        assert_eq!(rb.read(&mut buf).unwrap(), 3);
+        assert_eq!(rb.rewindable(), 3);
+
+        // Writing overwrites the oldest read byte.
+        assert_eq!(rb.write(&[5]).unwrap(), 1);
+        assert_eq!(rb.rewindable(), 2);
+
+        assert_eq!(rb.rewind_read(), 2);
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5]));
+        assert_eq!(rb.rewind_read(), 0);
+
+        rb.clear();
+        assert_eq!(rb.rewindable(), 0);
+    }
+
+    #[test]
+    fn ringbuf_rewind_read_wrapped() {
+        // Rewinding reads the bytes `with_offset` would discard, so they're written here.
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(4).unwrap();
+        assert_eq!(rb.write(&[5, 6]).unwrap(), 2);
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.read_pos, 0);
+        assert!(rb.eq_slice(&[6]));
+        assert_eq!(rb.rewindable(), 4);
+
+        // Moves the read position back across the start of the underlying buffer.
+        assert_eq!(rb.rewind_read(), 4);
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [2, 3, 4, 5, 6]);
+
+        assert_eq!(rb.rewind_read(), 5);
+        assert!(rb.eq_slice(&[2, 3, 4, 5, 6]));
+
+        rb.advance_read_pos(1).unwrap();
+        rb.insert_front(&[9]).unwrap();
+        assert_eq!(rb.rewindable(), 0);
+    }
+
+    #[test]
+    fn ringbuf_extend_from_slice_exact() {
+        let mut rb = with_offset(5, 3);
+
+        rb.extend_from_slice_exact(&[3, 4, 5, 6, 7]).unwrap();
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        rb.advance_read_pos(2).unwrap();
+        let err = rb.extend_from_slice_exact(&[8, 9, 10]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(&[5, 6, 7]));
+        assert_eq!(rb.write_pos, 2);
+
+        rb.extend_from_slice_exact(&[]).unwrap();
+        rb.extend_from_slice_exact(&[8, 9]).unwrap();
+        assert!(rb.eq_slice(&[5, 6, 7, 8, 9]));
+    }
+
+    #[test]
+    fn ringbuf_count_byte_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.count_byte(0), 0);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write(&[1, 3, 3]).unwrap(), 3);
+
+        assert_eq!(rb.count_byte(3), 3);
+        assert_eq!(rb.count_byte(4), 1);
+        assert_eq!(rb.count_byte(1), 1);
+        assert_eq!(rb.count_byte(0), 0);
+    }
+
+    #[test]
+    fn ringbuf_split_wrapped() {
+        let mut rb = with_offset(8, 5);
+        assert_eq!(rb.write(b"ab,cde,f").unwrap(), 8);
+
+        let segments: Vec<Vec<u8>> = rb.split(b',').collect();
+        assert_eq!(segments, [&b"ab"[..], b"cde", b"f"]);
+        assert_eq!(rb.len(), 8);
+
+        rb.advance_read_pos(7).unwrap();
+        assert_eq!(rb.write(b",").unwrap(), 1);
+        let segments: Vec<Vec<u8>> = rb.split(b',').collect();
+        assert_eq!(segments, [&b"f"[..], b""]);
+
+        rb.clear();
+        let segments: Vec<Vec<u8>> = rb.split(b',').collect();
+        assert_eq!(segments, [b""]);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    fn ringbuf_bytes_buf_wrapped() {
+        use bytes::Buf;
+
+        let mut rb = with_offset(8, 6);
+        assert_eq!(rb.write(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]).unwrap(), 6);
+
+        assert_eq!(Buf::remaining(&rb), 6);
+        assert_eq!(rb.chunk(), [0x12, 0x34, 0x56]);
+        assert_eq!(rb.get_u32(), 0x1234_5678);
+        assert_eq!(rb.get_u16_le(), 0xbc9a);
+        assert!(!rb.has_remaining());
+    }
+
+    #[test]
+    #[cfg(feature = "bytes-mut")]
+    fn ringbuf_bytes_buf_mut_wrapped() {
+        use bytes::{Buf, BufMut};
+
+        let mut rb = with_offset(8, 6);
+        assert_eq!(rb.chunk_mut().len(), 3);
+
+        // Spans the end of the underlying buffer.
+        rb.put_u32(0x1234_5678);
+        rb.put_slice(&[0x9a, 0xbc]);
+        assert_eq!(rb.remaining_mut(), 2);
+        assert_eq!(rb.get_u32(), 0x1234_5678);
+        assert_eq!(rb.get_u16_le(), 0xbc9a);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes-mut")]
+    #[should_panic]
+    fn ringbuf_bytes_buf_mut_put_past_end() {
+        use bytes::BufMut;
+
+        let mut rb = RingBuf::with_capacity(3);
+        rb.put_u32(0);
+    }
+
+    #[test]
+    #[cfg(feature = "bytes")]
+    #[should_panic]
+    fn ringbuf_bytes_buf_advance_past_end() {
+        use bytes::Buf;
+
+        let mut rb: RingBuf = vec![0, 1, 2].into();
+        rb.advance(4);
+    }
+
+    #[test]
+    fn ringbuf_reserve_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6]).unwrap();
+
+        rb.reserve(1);
+        assert_eq!(rb.capacity(), 5);
+
+        rb.reserve(2);
+        assert_eq!(rb.capacity(), 10);
+        assert_eq!(rb.read_pos, 0);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+
+        rb.reserve(7);
+        assert_eq!(rb.capacity(), 20);
+
+        rb.reserve(40);
+        assert_eq!(rb.capacity(), 44);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn ringbuf_reserve_exact_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6]).unwrap();
+
+        rb.reserve_exact(1);
+        assert_eq!(rb.capacity(), 5);
+
+        rb.reserve_exact(2);
+        assert_eq!(rb.capacity(), 6);
+        assert_eq!(rb.read_pos, 0);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+
+        assert_eq!(rb.write(&[7, 8, 9]).unwrap(), 2);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7, 8]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_reserve_overflow() {
+        let mut rb: RingBuf = vec![0].into();
+        rb.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn ringbuf_as_mut_slices_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mask = [0x0F, 0xF0];
+        let (first, second) = rb.as_mut_slices();
+        assert_eq!(first.len(), 3);
+        assert_eq!(second.len(), 2);
+        for (i, b) in first.iter_mut().chain(second.iter_mut()).enumerate() {
+            *b ^= mask[i % 2];
+        }
+
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(buf, [3 ^ 0x0F, 4 ^ 0xF0, 5 ^ 0x0F, 6 ^ 0xF0, 7 ^ 0x0F]);
+
+        let (first, second) = rb.as_mut_slices();
+        assert!(first.is_empty());
+        assert!(second.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_shrink_to_fit_wrapped() {
+        let mut rb = with_offset(5, 4);
+        rb.write_all(&[4, 5, 6]).unwrap();
+
+        rb.shrink_to_fit();
+        assert_eq!(rb.capacity(), 3);
+        assert_eq!(rb.data.len(), 4);
+        assert!(rb.eq_slice(&[4, 5, 6]));
+
+        let ptr = rb.data.as_ptr();
+        rb.shrink_to_fit();
+        assert_eq!(rb.data.as_ptr(), ptr);
+
+        rb.clear();
+        rb.shrink_to_fit();
+        assert_eq!(rb.capacity(), 0);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_peek_exact_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut buf = [0u8; 5];
+        rb.peek_exact(&mut buf).unwrap();
+        assert_eq!(buf, [3, 4, 5, 6, 7]);
+        assert_eq!(rb.len(), 5);
+
+        let mut buf = [9u8; 6];
+        let err = rb.peek_exact(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(buf, [9; 6]);
+
+        rb.peek_exact(&mut []).unwrap();
+    }
+
+    #[test]
+    fn ringbuf_append_grow_wrapped() {
+        let mut other = RingBuf::with_capacity(5);
+        assert_eq!(other.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        other.advance_read_pos(3).unwrap();
+        assert_eq!(other.write(&[5, 6, 7]).unwrap(), 3);
+
+        let mut rb = RingBuf::with_policy(4, FullPolicy::Grow);
+        assert_eq!(rb.write(&[0, 1, 2]).unwrap(), 3);
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.write(&[2]).unwrap(), 1);
+
+        rb.append(&mut other).unwrap();
+        assert!(rb.eq_slice(&[2, 2, 3, 4, 5, 6, 7]));
+        assert!(rb.capacity() >= 7);
+        assert!(other.is_empty());
+
+        rb.append(&mut other).unwrap();
+        assert_eq!(rb.len(), 7);
+    }
+
+    #[test]
+    fn ringbuf_append_reject_and_overwrite() {
+        let mut other: RingBuf = vec![3, 4, 5].into();
+
+        let mut rb = RingBuf::with_capacity(4);
+        assert_eq!(rb.write(&[0, 1]).unwrap(), 2);
+        let err = rb.append(&mut other).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(&[0, 1]));
+        assert!(other.eq_slice(&[3, 4, 5]));
+
+        rb.set_policy(FullPolicy::OverwriteOldest);
+        rb.append(&mut other).unwrap();
+        assert!(rb.eq_slice(&[1, 3, 4, 5]));
+        assert!(other.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_write_tail_larger_then_capacity() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write_tail(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]), 5);
+        assert!(rb.eq_slice(&[6, 7, 8, 9, 10]));
+        assert_eq!(rb.write_tail(&[11]), 0);
+
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write_tail(&[11, 12, 13, 14]), 3);
+        assert!(rb.eq_slice(&[9, 10, 12, 13, 14]));
+
+        rb.clear();
+        assert_eq!(rb.write_tail(&[1, 2]), 2);
+        assert!(rb.eq_slice(&[1, 2]));
+    }
+
+    #[test]
+    fn ringbuf_builder() {
+        let rb = RingBufBuilder::new().build();
+        assert_eq!(rb.capacity(), 0);
+        assert_eq!(rb.policy(), FullPolicy::Reject);
+
+        let rb = RingBufBuilder::new().capacity(5).build();
+        assert_eq!(rb.capacity(), 5);
+        assert!(rb.is_empty());
+
+        let rb = RingBufBuilder::new().power_of_two(true).build();
+        assert_eq!(rb.capacity(), 1);
+
+        for &(capacity, rounded) in &[(1, 1), (5, 8), (8, 8), (9, 16)] {
+            let rb = RingBufBuilder::new().capacity(capacity).power_of_two(true).build();
+            assert_eq!(rb.capacity(), rounded);
+
+            let rb = RingBufBuilder::new().capacity(capacity).power_of_two(false).build();
+            assert_eq!(rb.capacity(), capacity);
+        }
+
+        let rb = RingBufBuilder::new().capacity(3).fill(7).build();
+        assert_eq!(rb.capacity(), 3);
+        assert!(rb.eq_slice(&[7, 7, 7]));
+
+        let rb = RingBufBuilder::new().capacity(3).fill(7).power_of_two(true).build();
+        assert!(rb.eq_slice(&[7, 7, 7, 7]));
+    }
+
+    #[test]
+    fn ringbuf_builder_policy() {
+        let mut rb = RingBufBuilder::new()
+            .capacity(3)
+            .fill(0)
+            .policy(FullPolicy::OverwriteOldest)
+            .build();
+        assert_eq!(rb.write(&[1, 2]).unwrap(), 2);
+        assert!(rb.eq_slice(&[0, 1, 2]));
+
+        let mut rb = RingBufBuilder::new().capacity(3).fill(0).policy(FullPolicy::Grow).build();
+        assert_eq!(rb.write(&[1, 2]).unwrap(), 2);
+        assert!(rb.eq_slice(&[0, 0, 0, 1, 2]));
+
+        let mut rb = RingBufBuilder::new().capacity(3).fill(0).policy(FullPolicy::Reject).build();
+        assert_eq!(rb.write(&[1, 2]).unwrap(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ringbuf_builder_power_of_two_overflow() {
+        RingBufBuilder::new().capacity(usize::MAX).power_of_two(true).build();
+    }
+
+    #[test]
+    fn ringbuf_read_until_sequence_wrapped() {
+        let mut rb = with_offset(8, 5);
+        // The "\r\n" straddles the end of the underlying buffer.
+        assert_eq!(rb.write(b"a\rb\r\ncd").unwrap(), 7);
+        assert_eq!(rb.data[8], b'\r');
+
+        let mut out = vec![b'>'];
+        assert_eq!(rb.read_until_sequence(b"\r\n", &mut out).unwrap(), 5);
+        assert_eq!(out, b">a\rb\r\n");
+        assert!(rb.eq_slice(b"cd"));
+    }
+
+    #[test]
+    fn ringbuf_read_until_sequence_partial_delim() {
+        let mut rb: RingBuf = b"abc\r".to_vec().into();
+
+        let mut out = Vec::new();
+        assert_eq!(rb.read_until_sequence(b"\r\n", &mut out).unwrap(), 0);
+        assert!(out.is_empty());
+        assert_eq!(rb.len(), 4);
+
+        let err = rb.read_until_sequence(b"", &mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn ringbuf_is_contiguous() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert!(rb.is_contiguous());
+
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+        assert!(rb.is_contiguous());
+
+        rb.advance_read_pos(4).unwrap();
+        assert!(rb.is_contiguous());
+
+        // Ends exactly at the end of the underlying buffer.
+        assert_eq!(rb.write(&[4, 5]).unwrap(), 2);
+        assert_eq!(rb.write_pos, 0);
+        assert!(rb.is_contiguous());
+
+        assert_eq!(rb.write(&[6]).unwrap(), 1);
+        assert!(!rb.is_contiguous());
+        assert_eq!(rb.as_slices(), (&[4, 5][..], &[6][..]));
+    }
+
+    #[test]
+    fn ringbuf_write_all_grow() {
+        let mut rb = RingBuf::with_policy(4, FullPolicy::Grow);
+
+        let data: Vec<u8> = (0..=255).collect();
+        rb.write_all(&data[..3]).unwrap();
+        rb.advance_read_pos(2).unwrap();
+        rb.write_all(&data[3..]).unwrap();
+        assert!(rb.capacity() >= 254);
+
+        let mut out = Vec::new();
+        rb.read_to_end(&mut out).unwrap();
+        assert_eq!(out, &data[2..]);
+    }
+
+    #[test]
+    fn ringbuf_write_all_reject() {
+        let mut rb = RingBuf::with_capacity(4);
+
+        let err = rb.write_all(&[0, 1, 2, 3, 4, 5]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(&[0, 1, 2, 3]));
+    }
+
+    #[test]
+    fn ringbuf_high_water_mark() {
+        let mut rb = RingBuf::with_capacity(8);
+        assert_eq!(rb.high_water_mark(), 0);
+        assert_eq!(rb.utilization(), 0.0);
+
+        assert_eq!(rb.write(&[0, 1, 2]).unwrap(), 3);
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.write(&[3, 4, 5, 6, 7]).unwrap(), 5);
+        assert_eq!(rb.high_water_mark(), 6);
+        assert_eq!(rb.utilization(), 0.75);
+
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.read(&mut buf).unwrap(), 5);
+        assert_eq!(rb.write(&[8, 9]).unwrap(), 2);
+        assert_eq!(rb.len(), 3);
+        assert_eq!(rb.high_water_mark(), 6);
+
+        rb.clear();
+        assert_eq!(rb.high_water_mark(), 6);
+
+        rb.reset_high_water_mark();
+        assert_eq!(rb.high_water_mark(), 0);
+        rb.insert_front(&[0, 1]).unwrap();
+        assert_eq!(rb.high_water_mark(), 2);
+
+        let rb: RingBuf = vec![0, 1, 2].into();
+        assert_eq!(rb.high_water_mark(), 3);
+        assert_eq!(rb.utilization(), 1.0);
+
+        let rb = RingBuf::with_capacity(0);
+        assert_eq!(rb.utilization(), 0.0);
+    }
+
+    #[test]
+    fn ringbuf_trim_start_wrapped() {
+        let mut rb = with_offset(8, 6);
+        assert_eq!(rb.write(&[0, 0, 0, 0, 0, 7, 0, 8]).unwrap(), 8);
+
+        // The run of zeros continues past the end of the underlying buffer.
+        assert_eq!(rb.trim_start(|b| b == 0), 5);
+        assert!(rb.eq_slice(&[7, 0, 8]));
+
+        assert_eq!(rb.trim_start(|b| b == 0), 0);
+        assert_eq!(rb.trim_start(|_| true), 3);
+        assert!(rb.is_empty());
+        assert_eq!(rb.trim_start(|_| true), 0);
+    }
+
+    #[test]
+    fn ringbuf_advance_read_pos_clamped() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.advance_read_pos_clamped(3), 0);
+        assert_eq!(rb.read_pos, 0);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write(&[5, 6, 7]).unwrap(), 3);
+
+        assert_eq!(rb.advance_read_pos_clamped(1), 1);
+        assert!(rb.eq_slice(&[4, 5, 6, 7]));
+
+        assert_eq!(rb.advance_read_pos_clamped(usize::MAX), 4);
+        assert!(rb.is_empty());
+        assert_eq!(rb.read_pos, rb.write_pos);
+    }
+
+    #[test]
+    fn ringbuf_copy_range_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        let mut out = [0u8; 5];
+        // Before the end of the underlying buffer.
+        assert_eq!(rb.copy_range(0, 2, &mut out).unwrap(), 2);
+        assert_eq!(out[..2], [3, 4]);
+        // After the end of the underlying buffer.
+        assert_eq!(rb.copy_range(3, 5, &mut out).unwrap(), 2);
+        assert_eq!(out[..2], [6, 7]);
+        // Spanning the end of the underlying buffer.
+        assert_eq!(rb.copy_range(1, 5, &mut out).unwrap(), 4);
+        assert_eq!(out[..4], [4, 5, 6, 7]);
+
+        assert_eq!(rb.copy_range(0, 5, &mut out[..3]).unwrap(), 3);
+        assert_eq!(out[..3], [3, 4, 5]);
+        assert_eq!(rb.copy_range(5, 5, &mut out).unwrap(), 0);
+
+        assert_eq!(rb.copy_range(0, 6, &mut out).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(rb.copy_range(3, 2, &mut out).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(rb.len(), 5);
+    }
+
+    #[test]
+    fn ringbuf_write_or_would_block() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        assert_eq!(rb.write_or_would_block(&[0, 1, 2]).unwrap(), 3);
+        assert_eq!(rb.write_or_would_block(&[3, 4, 5]).unwrap(), 2);
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+
+        let err = rb.write_or_would_block(&[5]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WouldBlock);
+        assert_eq!(rb.write_or_would_block(&[]).unwrap(), 0);
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+
+        rb.set_policy(FullPolicy::OverwriteOldest);
+        assert_eq!(rb.write_or_would_block(&[5]).unwrap(), 1);
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn ringbuf_replace_contents() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        rb.replace_contents(&[9, 8, 7, 6, 5]).unwrap();
+        assert!(rb.eq_slice(&[9, 8, 7, 6, 5]));
+        assert_eq!(rb.read_pos, 0);
+        assert!(rb.is_contiguous());
+
+        let err = rb.replace_contents(&[0; 6]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(&[9, 8, 7, 6, 5]));
+
+        rb.replace_contents(&[]).unwrap();
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_peek_iter_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert_eq!(rb.peek_iter().next(), None);
+
+        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
+        rb.advance_read_pos(3).unwrap();
+        assert_eq!(rb.write(&[5, 6, 7]).unwrap(), 3);
+
+        let mut buf = [0u8; 5];
+        assert_eq!(rb.peek(&mut buf).unwrap(), 5);
+
+        let mut iter = rb.peek_iter();
+        assert_eq!(iter.len(), 5);
+        iter.next();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(rb.peek_iter().collect::<Vec<u8>>(), buf);
+        assert_eq!(rb.len(), 5);
+    }
+
+    #[test]
+    fn ringbuf_reader_view_wrapped() {
+        fn read_all(mut r: impl Read) -> Vec<u8> {
+            let mut out = Vec::new();
+            r.read_to_end(&mut out).unwrap();
+            out
+        }
+
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[3, 4, 5, 6, 7]).unwrap();
+
+        assert_eq!(read_all(rb.reader_view()), [3, 4, 5, 6, 7]);
+        assert_eq!(rb.len(), 5);
+
+        let mut view = rb.reader_view();
+        let mut buf = [0u8; 4];
+        assert_eq!(view.read(&mut buf).unwrap(), 4);
+        assert_eq!(buf, [3, 4, 5, 6]);
+        assert_eq!(view.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 7);
+        assert_eq!(view.read(&mut buf).unwrap(), 0);
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn ringbuf_ring_error_variants() {
+        fn ring_error(err: std::io::Error) -> (ErrorKind, RingError) {
+            (err.kind(), *err.get_ref().unwrap().downcast_ref::<RingError>().unwrap())
+        }
+
+        let mut rb = RingBuf::with_capacity(3);
+        rb.write_all(&[0, 1, 2]).unwrap();
+
+        assert_eq!(
+            ring_error(rb.extend_from_slice_exact(&[3]).unwrap_err()),
+            (ErrorKind::WriteZero, RingError::Full));
+        assert_eq!(
+            ring_error(rb.insert_front(&[3]).unwrap_err()),
+            (ErrorKind::WriteZero, RingError::Full));
+        assert_eq!(
+            ring_error(rb.replace_contents(&[0, 1, 2, 3]).unwrap_err()),
+            (ErrorKind::WriteZero, RingError::Overflow));
+        assert_eq!(
+            ring_error(rb.advance_read_pos(4).unwrap_err()),
+            (ErrorKind::InvalidInput, RingError::SeekPastWrite));
+        assert_eq!(
+            ring_error(rb.peek_exact(&mut [0u8; 4]).unwrap_err()),
+            (ErrorKind::UnexpectedEof, RingError::UnexpectedEof));
+        assert!(rb.eq_slice(&[0, 1, 2]));
+
+        let err: std::io::Error = RingError::Full.into();
+        assert_eq!(err.to_string(), "Not enough free space.");
+    }
+
+    #[test]
+    fn ringbuf_ring_error_variants_extended() {
+        fn ring_error(err: std::io::Error) -> (ErrorKind, RingError) {
+            (err.kind(), *err.get_ref().unwrap().downcast_ref::<RingError>().unwrap())
+        }
+
+        let mut rb = RingBuf::with_capacity(4);
+        rb.write_all(&[0, 1]).unwrap();
+
+        assert_eq!(
+            ring_error(rb.read_bits(17).unwrap_err()),
+            (ErrorKind::UnexpectedEof, RingError::UnexpectedEof));
+        assert_eq!(
+            ring_error(rb.drain_exact(2, &mut [0u8; 1]).unwrap_err()),
+            (ErrorKind::InvalidInput, RingError::OutputTooSmall));
+        assert_eq!(
+            ring_error(rb.copy_range(1, 3, &mut [0u8; 4]).unwrap_err()),
+            (ErrorKind::InvalidInput, RingError::RangeOutOfBounds));
+        assert_eq!(
+            ring_error(rb.zero_range(1, 2).unwrap_err()),
+            (ErrorKind::InvalidInput, RingError::RangeOutOfBounds));
+        assert_eq!(
+            ring_error(rb.fill_from_exact(&mut &[0u8; 3][..], 3).unwrap_err()),
+            (ErrorKind::InvalidInput, RingError::CommitPastFree));
+        assert!(rb.eq_slice(&[0, 1]));
+
+        rb.clear();
+        rb.write_all(&[9, 0]).unwrap();
+        assert_eq!(
+            ring_error(rb.try_read_frame(1, true).unwrap_err()),
+            (ErrorKind::InvalidData, RingError::FrameTooLarge));
+        assert_eq!(
+            ring_error(RingBuf::from_parts(vec![0; 4].into_boxed_slice(), 4, 0).err().unwrap()),
+            (ErrorKind::InvalidInput, RingError::PositionOutOfBounds));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn ringbuf_pod_wrapped() {
+        #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+        #[repr(C)]
+        struct Header {
+            id: u16,
+            flags: u16,
+            len: u32
+        }
+
+        let header = Header { id: 7, flags: 0xabcd, len: 0x1234_5678 };
+        let mut rb = RingBuf::with_capacity(12);
+
+        rb.write_pod(&header).unwrap();
+        assert_eq!(rb.len(), 8);
+        assert_eq!(rb.read_pod::<Header>().unwrap(), header);
+
+        // The second header straddles the end of the underlying buffer.
+        rb.write_pod(&header).unwrap();
+        assert!(!rb.is_contiguous());
+        assert_eq!(rb.read_pod::<Header>().unwrap(), header);
+        assert!(rb.is_empty());
+
+        assert_eq!(rb.read_pod::<Header>().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        rb.write_all(&[0; 9]).unwrap();
+        assert_eq!(rb.write_pod(&header).unwrap_err().kind(), ErrorKind::WriteZero);
+        assert_eq!(rb.len(), 9);
+    }
+
+    #[test]
+    fn ringbuf_lines_partial() {
+        let mut rb = with_offset(12, 8);
+        rb.write_all(b"ab\ncd\nef").unwrap();
+        assert!(!rb.is_contiguous());
+
+        let mut lines = rb.lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "ab\n");
+        assert_eq!(lines.next().unwrap().unwrap(), "cd\n");
+        assert!(lines.next().is_none());
+        assert!(rb.eq_slice(b"ef"));
+
+        rb.write_all(&[0xff, b'\n', b'g', b'\n']).unwrap();
+        let mut lines = rb.lines();
+        assert_eq!(lines.next().unwrap().unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(lines.next().unwrap().unwrap(), "g\n");
+        assert!(lines.next().is_none());
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_from_vec_is_full() {
+        let mut rb: RingBuf = vec![0, 1, 2].into();
+        assert_eq!(rb.capacity(), 3);
+        assert_eq!(rb.len(), 3);
+        assert_eq!(rb.remaining(), 0);
+        assert!(rb.is_full());
+        assert_eq!(rb.high_water_mark(), 3);
+
+        let mut buf = [0u8; 1];
+        rb.read_exact(&mut buf).unwrap();
+        assert!(!rb.is_full());
+        assert_eq!(rb.write(&[3, 4]).unwrap(), 1);
+        assert!(rb.is_full());
+
+        let rb: RingBuf = Vec::new().into();
+        assert_eq!(rb.capacity(), 0);
+        assert!(rb.is_empty());
+        assert!(rb.is_full());
+    }
+
+    #[test]
+    fn ringbuf_rfind_wrapped() {
+        let mut rb = with_offset(6, 4);
+        rb.write_all(&[1, 0, 1, 2, 0, 3]).unwrap();
+        assert!(!rb.is_contiguous());
+
+        // The last 0 is in the wrapped part, at the start of the underlying buffer.
+        assert_eq!(rb.rfind(0), Some(4));
+        assert_eq!(rb.find(0), Some(1));
+        // The last 1 is before the wrap.
+        assert_eq!(rb.rfind(1), Some(2));
+        assert_eq!(rb.rfind(3), Some(5));
+        assert_eq!(rb.rfind(9), None);
+
+        rb.clear();
+        assert_eq!(rb.rfind(0), None);
+    }
+
+    #[test]
+    fn ringbuf_write_checked() {
+        let mut rb = RingBuf::with_capacity(3);
+        assert_eq!(rb.write_checked(&[0, 1, 2]), (3, false));
+        assert_eq!(rb.write_checked(&[]), (0, false));
+        assert_eq!(rb.write_checked(&[3]), (0, true));
+
+        let mut rb = RingBuf::with_policy(3, FullPolicy::OverwriteOldest);
+        assert_eq!(rb.write_checked(&[0, 1, 2, 3, 4]), (3, true));
+        assert!(rb.eq_slice(&[0, 1, 2]));
+    }
+
+    #[test]
+    fn ringbuf_into_vec() {
+        // Contiguous from the start of the underlying buffer, so the allocation is reused.
+        let mut rb = RingBuf::with_capacity(5);
+        rb.write_all(&[0, 1, 2]).unwrap();
+        let vec = rb.into_vec();
+        assert_eq!(vec, [0, 1, 2]);
+        assert_eq!(vec.capacity(), 6);
+
+        let mut rb = RingBuf::with_capacity(5);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(3).unwrap();
+        rb.write_all(&[5, 6, 7]).unwrap();
+        assert!(!rb.is_contiguous());
+        let vec = rb.into_vec();
+        assert_eq!(vec, [3, 4, 5, 6, 7]);
+        assert_eq!(vec.capacity(), 5);
+
+        assert!(RingBuf::with_capacity(3).into_vec().is_empty());
+    }
+
+    #[test]
+    fn ringbuf_peek_remaining() {
+        let mut rb = RingBuf::with_capacity(5);
+        let (first, second) = rb.peek_remaining();
+        assert!(first.is_empty() && second.is_empty());
+
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(2).unwrap();
+        rb.write_all(&[5, 6]).unwrap();
+
+        let (first, second) = rb.peek_remaining();
+        assert_eq!(first.len() + second.len(), rb.len());
+        assert_eq!(first, [2, 3, 4, 5]);
+        assert_eq!(second, [6]);
+    }
+
+    #[test]
+    fn ringbuf_force_write() {
+        let mut rb = RingBuf::with_capacity(4);
+
+        // Fits in the free space, nothing is dropped.
+        rb.write_all(&[0, 1]).unwrap();
+        assert_eq!(rb.force_write(&[2]), 0);
+        assert!(rb.eq_slice(&[0, 1, 2]));
+
+        // Partially full, one byte more than fits.
+        assert_eq!(rb.force_write(&[3, 4]), 1);
+        assert!(rb.eq_slice(&[1, 2, 3, 4]));
+
+        // Full, and larger than the capacity.
+        assert_eq!(rb.force_write(&[5, 6, 7, 8, 9, 10]), 4);
+        assert!(rb.eq_slice(&[7, 8, 9, 10]));
+        assert_eq!(rb.policy(), FullPolicy::Reject);
+    }
+
+    #[test]
+    fn ringbuf_try_collect_from() {
+        let rb = RingBuf::try_collect_from(vec![0, 1, 2], 3).unwrap();
+        assert!(rb.is_full());
+        assert!(rb.eq_slice(&[0, 1, 2]));
+
+        let rb = RingBuf::try_collect_from(vec![0], 3).unwrap();
+        assert_eq!(rb.capacity(), 3);
+        assert!(rb.eq_slice(&[0]));
+
+        let err = RingBuf::try_collect_from(vec![0, 1, 2, 3], 3).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+
+        assert!(RingBuf::try_collect_from(std::iter::empty(), 0).unwrap().is_empty());
+        assert!(RingBuf::try_collect_from(std::iter::once(0), 0).is_err());
+    }
+
+    #[test]
+    fn ringbuf_keep_last_wrapped() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(4).unwrap();
+        rb.write_all(&[5, 6, 7, 8]).unwrap();
+        assert!(!rb.is_contiguous());
+
+        assert_eq!(rb.keep_last(2), 3);
+        assert!(rb.eq_slice(&[7, 8]));
+
+        assert_eq!(rb.keep_last(0), 2);
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_write_vectored_all_atomic() {
+        let mut rb = with_offset(6, 4);
+
+        let header = [1, 2];
+        let body = [3, 4, 5];
+        rb.write_vectored_all(&[IoSlice::new(&header), IoSlice::new(&body)]).unwrap();
+        assert!(!rb.is_contiguous());
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5]));
+
+        let err = rb.write_vectored_all(&[IoSlice::new(&[6]), IoSlice::new(&[7])]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5]));
+
+        rb.write_vectored_all(&[IoSlice::new(&[]), IoSlice::new(&[6])]).unwrap();
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn ringbuf_seek_read_relative() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.seek_read_relative(4).unwrap();
+        assert!(rb.eq_slice(&[4]));
+        rb.seek_read_relative(0).unwrap();
+        assert_eq!(rb.len(), 1);
+
+        // Writing 3 bytes overwrites the 3 oldest read bytes, leaving 1 to seek back over.
+        rb.write_all(&[5, 6, 7]).unwrap();
+        assert_eq!(rb.rewindable(), 1);
+        let err = rb.seek_read_relative(-2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(
+            err.get_ref().unwrap().downcast_ref::<RingError>(),
+            Some(&RingError::SeekBeforeRewindable));
+        assert!(rb.eq_slice(&[4, 5, 6, 7]));
+
+        rb.seek_read_relative(-1).unwrap();
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        rb.seek_read_relative(3).unwrap();
+        rb.seek_read_relative(-3).unwrap();
+        assert!(rb.eq_slice(&[3, 4, 5, 6, 7]));
+
+        assert_eq!(rb.seek_read_relative(6).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(rb.len(), 5);
+    }
+
+    #[test]
+    fn ringbuf_write_slices_fills() {
+        let mut rb = with_offset(6, 3);
+
+        assert_eq!(rb.write_slices(&[&[1, 2], &[3, 4], &[5, 6, 7]]), 6);
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5, 6]));
+        assert_eq!(rb.write_slices(&[&[8]]), 0);
+        assert_eq!(rb.write_slices(&[]), 0);
+    }
+
+    #[test]
+    fn ringbuf_commit_write_read() {
+        let mut rb = RingBuf::with_capacity(4);
+
+        rb.write_all(&[0, 1, 2]).unwrap();
+        rb.commit_read(3).unwrap();
+        rb.commit_write(4).unwrap();
+        assert!(rb.is_full());
+        assert_eq!(offsets(&rb), (3, 2));
+        assert_eq!(rb.high_water_mark(), 4);
+
+        let err = rb.commit_write(1).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.get_ref().unwrap().downcast_ref::<RingError>(), Some(&RingError::CommitPastFree));
+        assert_eq!(rb.commit_read(5).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(offsets(&rb), (3, 2));
+
+        rb.commit_read(4).unwrap();
+        assert!(rb.is_empty());
+        rb.commit_write(0).unwrap();
+        assert!(rb.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_read_or_would_block() {
+        let mut rb = RingBuf::with_capacity(4);
+        let mut buf = [0u8; 3];
+
+        assert_eq!(rb.read_or_would_block(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+        assert_eq!(rb.read_or_would_block(&mut []).unwrap(), 0);
+
+        rb.write_all(&[0, 1, 2, 3]).unwrap();
+        assert_eq!(rb.read_or_would_block(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [0, 1, 2]);
+        assert_eq!(rb.read_or_would_block(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 3);
+        assert_eq!(rb.read_or_would_block(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    }
+
+    #[test]
+    fn ringbuf_contiguous_free_mut() {
+        let mut rb = RingBuf::with_capacity(5);
+
+        // Not wrapped, free space runs to the end of the underlying buffer, minus one byte.
+        rb.write_all(&[0, 1]).unwrap();
+        assert_eq!(rb.contiguous_free_mut().len(), 3);
+
+        // Free space wraps, the first run ends at the end of the underlying buffer.
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(rb.contiguous_free_mut().len(), 4);
+        rb.contiguous_free_mut().copy_from_slice(&[2, 3, 4, 5]);
+        rb.commit_write(4).unwrap();
+        assert_eq!(offsets(&rb), (2, 0));
+
+        // Contents wrap, the free space ends one byte before the read position.
+        assert_eq!(rb.contiguous_free_mut().len(), 1);
+        rb.contiguous_free_mut()[0] = 6;
+        rb.commit_write(1).unwrap();
+        assert!(rb.eq_slice(&[2, 3, 4, 5, 6]));
+
+        assert!(rb.contiguous_free_mut().is_empty());
+    }
+
+    #[test]
+    fn ringbuf_clone_into() {
+        let mut rb = RingBuf::with_capacity(5);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(3).unwrap();
+        rb.write_all(&[5, 6]).unwrap();
+
+        // Large enough, the underlying buffer of `dest` is reused.
+        let mut dest = RingBuf::with_capacity(6);
+        dest.write_all(&[9; 6]).unwrap();
+        rb.clone_into(&mut dest);
+        assert!(dest.eq_slice(&[3, 4, 5, 6]));
+        assert_eq!(dest.capacity(), 6);
+        assert_eq!(offsets(&dest), (0, 4));
+
+        // Too small, `dest` is given the capacity of the source.
+        let mut dest = RingBuf::with_policy(2, FullPolicy::Grow);
+        rb.clone_into(&mut dest);
+        assert!(dest.eq_slice(&[3, 4, 5, 6]));
+        assert_eq!(dest.capacity(), 5);
+        assert_eq!(dest.policy(), FullPolicy::Grow);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn ringbuf_try_read_frame() {
+        let mut rb = RingBuf::with_capacity(8);
+
+        // Complete frame, followed by the start of the next one.
+        rb.write_all(&[4, 0, 1, 2, 3, 4, 0, 0]).unwrap();
+        assert_eq!(rb.try_read_frame(2, false).unwrap(), Some(vec![1, 2, 3, 4]));
+        assert!(rb.eq_slice(&[0, 0]));
+
+        // The prefix of the next frame straddles the wrap, and its body is missing.
+        rb.write_all(&[0, 3]).unwrap();
+        assert!(!rb.is_contiguous());
+        assert_eq!(rb.try_read_frame(4, true).unwrap(), None);
+        assert_eq!(rb.len(), 4);
+
+        rb.write_all(&[4, 5, 6]).unwrap();
+        assert_eq!(rb.try_read_frame(4, true).unwrap(), Some(vec![4, 5, 6]));
+        assert!(rb.is_empty());
+
+        rb.write_all(&[9, 0]).unwrap();
+        assert_eq!(rb.try_read_frame(1, true).unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(rb.try_read_frame(9, true).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(rb.len(), 2);
+    }
+
+    #[test]
+    fn ringbuf_write_fmt() {
+        let mut rb = with_offset(8, 4);
+
+        let value = 4321;
+        write!(rb, "n={}!", value).unwrap();
+        assert!(rb.eq_slice(b"n=4321!"));
+
+        // Doesn't fit, so it's truncated and fails under the default policy.
+        let err = write!(rb, "{}{}", 5, 67).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(b"n=4321!5"));
+
+        let mut rb = RingBuf::with_policy(4, FullPolicy::OverwriteOldest);
+        write!(rb, "{}-{}", 12, 345).unwrap();
+        assert!(rb.eq_slice(b"-345"));
+    }
+
+    #[test]
+    fn ringbuf_write_fmt_full() {
+        let value = 67;
+
+        // Nearly fills the ring buffer, wrapping around the end of the underlying buffer.
+        let mut rb = with_offset(8, 5);
+        let word = "abcd";
+        write!(rb, "{}:{}", value, word).unwrap();
+        assert!(rb.eq_slice(b"67:abcd"));
+        assert_eq!(rb.remaining(), 1);
+
+        // Each formatted piece goes through `write`, so a full ring buffer follows its policy.
+        let mut rb = RingBuf::with_policy(4, FullPolicy::OverwriteOldest);
+        rb.write_all(b"abcd").unwrap();
+        write!(rb, "{}!", value).unwrap();
+        assert!(rb.eq_slice(b"d67!"));
+
+        let mut rb = RingBuf::with_policy(4, FullPolicy::Grow);
+        rb.write_all(b"abcd").unwrap();
+        write!(rb, "{}!", value).unwrap();
+        assert!(rb.eq_slice(b"abcd67!"));
+        assert_eq!(rb.capacity(), 8);
+
+        let mut rb = RingBuf::with_capacity(4);
+        rb.write_all(b"abcd").unwrap();
+        assert_eq!(write!(rb, "{}!", value).unwrap_err().kind(), ErrorKind::WriteZero);
+        assert!(rb.eq_slice(b"abcd"));
+    }
+
+    #[test]
+    fn ringbuf_peek_cow() {
+        let mut rb = RingBuf::with_capacity(5);
+        assert!(matches!(rb.peek_cow(), Cow::Borrowed(&[])));
+
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(3).unwrap();
+        assert!(matches!(rb.peek_cow(), Cow::Borrowed(_)));
+
+        rb.write_all(&[5, 6]).unwrap();
+        let contents = rb.peek_cow();
+        assert!(matches!(contents, Cow::Owned(_)));
+        assert_eq!(*contents, [3, 4, 5, 6]);
+        assert_eq!(*contents, *rb.to_vec());
+    }
+
+    #[test]
+    fn ringbuf_positions_reset_when_emptied() {
+        let mut rb = RingBuf::with_capacity(5);
+        rb.set_reset_on_empty(true);
+        let mut buf = [0u8; 5];
+
+        rb.write_all(&[0, 1, 2]).unwrap();
+        rb.read_exact(&mut buf[..3]).unwrap();
+        assert_eq!(offsets(&rb), (0, 0));
+        assert_eq!(rb.available_contiguous_write(), 5);
+
+        rb.write_all(&[0, 1, 2]).unwrap();
+        rb.advance_read_pos(1).unwrap();
+        rb.advance_read_pos(2).unwrap();
+        assert_eq!(offsets(&rb), (0, 0));
+
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+        rb.advance_read_pos(4).unwrap();
+        rb.write_all(&[5, 6]).unwrap();
+        assert_eq!(rb.advance_read_pos_clamped(10), 3);
+        assert_eq!(offsets(&rb), (0, 0));
+
+        rb.write_all(&[0, 0, 1]).unwrap();
+        assert_eq!(rb.trim_start(|b| b == 0), 2);
+        assert_eq!(rb.keep_last(0), 1);
+        assert_eq!(offsets(&rb), (0, 0));
+
+        rb.write_all(b"ab\n").unwrap();
+        let mut line = String::new();
+        rb.read_line(&mut line).unwrap();
+        assert_eq!(offsets(&rb), (0, 0));
+
+        // Emptying the ring buffer also ends rewinding.
+        assert_eq!(rb.rewindable(), 0);
+        assert_eq!(rb.rewind_read(), 0);
+    }
+
+    #[test]
+    fn ringbuf_rolling_hash_wrapped() {
+        fn hash(window: &[u8]) -> u64 {
+            window.iter().fold(0u64, |hash, &b| hash.wrapping_mul(257).wrapping_add(u64::from(b)))
+        }
+
+        let mut rb = with_offset(16, 10);
+        let bytes: Vec<u8> = (0..16u8).map(|i| i.wrapping_mul(37).wrapping_add(200)).collect();
+        rb.write_all(&bytes).unwrap();
+        assert!(!rb.is_contiguous());
+
+        for window in [1, 3, 9, 16].iter().copied() {
+            let expected: Vec<u64> = bytes.windows(window).map(hash).collect();
+            assert_eq!(rb.rolling_hash(window), expected);
+        }
+
+        assert!(rb.rolling_hash(17).is_empty());
+        assert_eq!(rb.slide(4).len(), 16);
+    }
+
+    #[test]
+    fn ringbuf_drain_exact_wrapped() {
+        let mut rb = with_offset(5, 3);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+
+        let mut out = [0u8; 4];
+        rb.drain_exact(4, &mut out).unwrap();
+        assert_eq!(out, [0, 1, 2, 3]);
+        assert!(rb.eq_slice(&[4]));
+
+        let mut out = [9u8; 2];
+        assert_eq!(rb.drain_exact(2, &mut out).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(rb.drain_exact(3, &mut out).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(out, [9, 9]);
+        assert!(rb.eq_slice(&[4]));
+
+        rb.drain_exact(0, &mut []).unwrap();
+        assert_eq!(rb.len(), 1);
+    }
+
+    #[test]
+    fn ringbuf_rewind_write_wrapped() {
+        let mut rb = with_offset(5, 4);
+        rb.write_all(&[0, 1, 2, 3]).unwrap();
+        assert_eq!(offsets(&rb), (4, 2));
+
+        // Moves the write position back across the start of the underlying buffer.
+        rb.rewind_write(3).unwrap();
+        assert_eq!(offsets(&rb), (4, 5));
+        assert!(rb.eq_slice(&[0]));
+
+        let err = rb.rewind_write(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert_eq!(err.get_ref().unwrap().downcast_ref::<RingError>(), Some(&RingError::RewindPastRead));
+        assert!(rb.eq_slice(&[0]));
+
+        rb.write_all(&[4, 5]).unwrap();
+        assert!(rb.eq_slice(&[0, 4, 5]));
+
+        rb.rewind_write(3).unwrap();
+        assert!(rb.is_empty());
+        assert_eq!(offsets(&rb), (4, 4));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::io::{Read, Write};
+    #[test]
+    fn ringbuf_max_contiguous_free() {
+        let mut rb = with_offset(8, 5);
+        rb.write_all(&[0, 1]).unwrap();
 
-    use crate::RingBuf;
+        // The free space is split between the end and the start of the underlying buffer.
+        assert_eq!(rb.contiguous_free_now(), 2);
+        assert_eq!(rb.max_contiguous_free(), 6);
+
+        assert_eq!(rb.write_with_min(6, |buf| buf.len()), 6);
+        assert_eq!(rb.contiguous_free_now(), 0);
+        assert_eq!(rb.max_contiguous_free(), 0);
+    }
 
     #[test]
-    fn ringbuf_with_capacity() {
-        let rb = RingBuf::with_capacity(4);
+    fn ringbuf_chain_spanning_read() {
+        let mut first = with_offset(4, 3);
+        first.write_all(&[0, 1, 2]).unwrap();
+        let second: RingBuf = vec![3, 4, 5, 6].into();
 
-        assert_eq!(rb.capacity(), 4);
-        assert_eq!(rb.len(), 0);
+        let mut chain = first.chain(second);
+        assert_eq!(chain.len(), 7);
+
+        let mut buf = [0u8; 2];
+        assert_eq!(chain.read(&mut buf).unwrap(), 2);
+        assert_eq!(buf, [0, 1]);
+
+        // Starts in the first ring buffer and ends in the second.
+        let mut buf = [0u8; 3];
+        assert_eq!(chain.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf, [2, 3, 4]);
+        assert_eq!(chain.len(), 2);
+
+        let mut rest = Vec::new();
+        chain.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, [5, 6]);
+        assert!(chain.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_write_with_result() {
+        let mut rb = RingBuf::with_capacity(4);
+
+        let value = rb.write_with_result(|buf| {
+            buf[..2].copy_from_slice(&[0, 1]);
+            Ok::<_, ()>((2, "ok"))
+        });
+        assert_eq!(value, Ok("ok"));
+        assert!(rb.eq_slice(&[0, 1]));
+
+        let err = rb.write_with_result(|buf| {
+            buf[0] = 2;
+            Err::<(usize, ()), _>(std::io::Error::new(ErrorKind::ConnectionReset, "reset"))
+        });
+        assert_eq!(err.unwrap_err().kind(), ErrorKind::ConnectionReset);
+        assert!(rb.eq_slice(&[0, 1]));
+        assert_eq!(offsets(&rb), (0, 2));
+    }
+
+    #[test]
+    fn ringbuf_iter_slices() {
+        let mut rb = with_offset(5, 3);
+        assert_eq!(rb.iter_slices().count(), 0);
+
+        rb.write_all(&[0, 1]).unwrap();
+        assert_eq!(rb.iter_slices().collect::<Vec<_>>(), [&[0, 1][..]]);
+
+        rb.write_all(&[2, 3]).unwrap();
+        assert_eq!(rb.iter_slices().collect::<Vec<_>>(), [&[0, 1, 2][..], &[3][..]]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "write truncated 4 bytes to 3")]
+    fn ringbuf_panic_on_truncate() {
+        let mut rb = RingBuf::with_capacity(3);
+        rb.set_panic_on_truncate(true);
+
+        let _ = rb.write(&[0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn ringbuf_panic_on_truncate_other_policies() {
+        let mut rb = RingBuf::with_policy(3, FullPolicy::OverwriteOldest);
+        rb.set_panic_on_truncate(true);
+        assert_eq!(rb.write(&[0, 1, 2, 3]).unwrap(), 4);
+
+        rb.set_panic_on_truncate(false);
+        rb.set_policy(FullPolicy::Reject);
+        assert_eq!(rb.write(&[4]).unwrap(), 0);
+    }
+
+    #[test]
+    fn ringbuf_read_into_uninit_wrapped() {
+        let mut rb = with_offset(6, 2);
+        rb.write_all(&[0, 1, 2, 3, 4, 5]).unwrap();
+        rb.advance_read_pos(1).unwrap();
+
+        let mut buf = [MaybeUninit::uninit(); 8];
+        assert_eq!(rb.read_into_uninit(&mut buf[..2]), 2);
+        assert!(rb.eq_slice(&[3, 4, 5]));
+
+        // The rest wraps around the end of the underlying buffer,
+        // and is read without moving the contents first.
+        assert!(!rb.is_contiguous());
+        assert_eq!(offsets(&rb), (5, 1));
+        assert_eq!(rb.read_into_uninit(&mut buf), 3);
         assert!(rb.is_empty());
+        assert_eq!(offsets(&rb), (1, 1));
+        assert_eq!(rb.read_into_uninit(&mut buf), 0);
     }
 
     #[test]
-    fn ringbuf_from_vec() {
-        let mut rb: RingBuf = vec![5, 4, 3, 2, 1].into();
+    fn ringbuf_peek_cursor_wrapped_record() {
+        fn parse(rb: &mut RingBuf) -> Option<(u8, Vec<u8>)> {
+            let mut cursor = rb.peek_cursor();
+            let kind = cursor.read_u8()?;
+            let len = usize::from(cursor.read_u16_be()?);
+            let mut body = Vec::new();
+            for _ in 0..len {
+                body.push(cursor.read_u8()?);
+            }
+            cursor.skip(1)?;
+            let mark = cursor.into_mark();
+            rb.commit(mark).unwrap();
+            Some((kind, body))
+        }
 
-        assert_eq!(rb.capacity(), 5);
+        // The length field straddles the end of the underlying buffer.
+        let mut rb = with_offset(8, 7);
+        rb.write_all(&[4, 0, 2, 0xaa, 0xbb]).unwrap();
+        assert!(!rb.is_contiguous());
+
+        // The trailer is missing, so nothing is consumed.
+        assert_eq!(parse(&mut rb), None);
         assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
 
-        let mut buf = [0u8; 10];
-        assert_eq!(rb.peek(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [5, 4, 3, 2, 1, 0, 0, 0, 0, 0]);
+        rb.write_all(&[0xff, 1]).unwrap();
+        assert_eq!(parse(&mut rb), Some((4, vec![0xaa, 0xbb])));
+        assert!(rb.eq_slice(&[1]));
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+    #[test]
+    fn ringbuf_fill_from_exact() {
+        let mut rb = with_offset(6, 4);
+        rb.write_all(&[0]).unwrap();
 
-        buf = [0u8; 10];
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [5, 4, 3, 2, 1, 0, 0, 0, 0, 0]);
+        // Fills the end of the underlying buffer, then wraps to its start.
+        let mut reader = &[1, 2, 3, 4, 5, 6][..];
+        rb.fill_from_exact(&mut reader, 4).unwrap();
+        assert!(!rb.is_contiguous());
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+        assert_eq!(reader, [5, 6]);
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 0);
+        assert_eq!(rb.fill_from_exact(&mut reader, 2).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(reader, [5, 6]);
+
+        rb.advance_read_pos(3).unwrap();
+        let err = rb.fill_from_exact(&mut reader, 3).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert!(rb.eq_slice(&[3, 4, 5, 6]));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn ringbuf_structural_eq() {
+        let mut a = with_offset(4, 3);
+        a.write_all(&[0, 1, 2]).unwrap();
+        let b: RingBuf = vec![0, 1, 2].into();
+        let c: RingBuf = vec![0, 1, 2, 3].into();
+
+        assert!(a == b);
+        assert!(!a.structural_eq(&b));
+        assert!(a != c);
+
+        let mut d = RingBuf::with_capacity(4);
+        d.write_all(&[0, 1, 2]).unwrap();
+        assert!(a.structural_eq(&d));
+        d.write_all(&[3]).unwrap();
+        assert!(!a.structural_eq(&d));
+    }
+
+    #[test]
+    fn ringbuf_write_reporting() {
+        let mut rb = with_offset(5, 2);
+
+        // Ends exactly at the end of the underlying buffer, so it doesn't wrap.
+        assert_eq!(rb.write_reporting(&[0, 1, 2, 3]), (4, false));
+        assert_eq!(offsets(&rb), (2, 0));
+        assert_eq!(rb.write_reporting(&[4]), (1, false));
+
+        assert_eq!(rb.write_reporting(&[5]), (0, false));
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+
+        let mut rb = with_offset(5, 4);
+        assert_eq!(rb.write_reporting(&[0]), (1, false));
+        assert_eq!(rb.write_reporting(&[1, 2]), (2, true));
+        assert!(rb.eq_slice(&[0, 1, 2]));
+
+        let mut rb = with_offset(3, 1);
+        rb.set_policy(FullPolicy::OverwriteOldest);
+        assert_eq!(rb.write_reporting(&[0, 1]), (2, false));
+        assert_eq!(rb.write_reporting(&[2, 3]), (2, true));
+        assert!(rb.eq_slice(&[1, 2, 3]));
+
+        rb.set_policy(FullPolicy::Grow);
+        assert_eq!(rb.write_reporting(&[4, 5]), (2, false));
+        assert!(rb.eq_slice(&[1, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn ringbuf_zero_range_wrapped() {
+        let mut rb = with_offset(6, 3);
+        rb.write_all(&[1, 2, 3, 4, 5, 6]).unwrap();
+        assert!(!rb.is_contiguous());
+
+        // Starts before the end of the underlying buffer and ends after its start.
+        rb.zero_range(2, 3).unwrap();
+        assert!(rb.eq_slice(&[1, 2, 0, 0, 0, 6]));
+        assert_eq!(rb.data[..2], [0, 6]);
+
+        rb.zero_range(5, 1).unwrap();
+        rb.zero_range(6, 0).unwrap();
+        assert!(rb.eq_slice(&[1, 2, 0, 0, 0, 0]));
+
+        assert_eq!(rb.zero_range(0, 7).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(rb.zero_range(1, usize::MAX).unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert!(rb.eq_slice(&[1, 2, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn ringbuf_zero_on_clear() {
+        let mut rb = with_offset(6, 4);
+        rb.set_zero_on_clear(true);
+
+        rb.write_all(b"secret").unwrap();
+        rb.advance_read_pos(2).unwrap();
+        rb.clear();
+        assert!(rb.data.iter().all(|&b| b == 0));
+
+        rb.set_zero_on_clear(false);
+        rb.write_all(b"public").unwrap();
+        rb.clear();
+        assert_eq!(rb.data[..6], *b"public");
+    }
+
+    #[test]
+    fn ringbuf_buffered_writer() {
+        let mut writer = RingBuf::with_capacity(4).into_buffered_writer(Vec::new());
+
+        for chunk in [&b"ab"[..], b"cde", b"f", b"ghijkl", b"m"].iter() {
+            writer.write_all(chunk).unwrap();
+        }
+        // "ab" was flushed to make room for "cde", "cdef" for the large "ghijkl",
+        // which bypassed the ring buffer.
+        assert_eq!(writer.get_ref(), b"abcdefghijkl");
+        assert!(writer.buffer().eq_slice(b"m"));
+
+        writer.flush().unwrap();
+        assert_eq!(writer.get_ref(), b"abcdefghijklm");
+        assert!(writer.buffer().is_empty());
+
+        // Bytes already in the ring buffer are written first.
+        let mut rb = RingBuf::with_capacity(4);
+        rb.write_all(b"xy").unwrap();
+        let mut writer = rb.into_buffered_writer(Vec::new());
+        writer.write_all(b"z").unwrap();
+        assert_eq!(writer.into_inner().unwrap(), b"xyz");
+    }
+
+    #[test]
+    fn ringbuf_take() {
+        let mut rb = with_offset(6, 4);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+
+        let mut take = rb.take(3);
+        let mut buf = [0u8; 5];
+        assert_eq!(take.read(&mut buf).unwrap(), 3);
+        assert_eq!(buf[..3], [0, 1, 2]);
+        assert_eq!(take.limit(), 0);
+        assert_eq!(take.read(&mut buf).unwrap(), 0);
+
+        let rb = take.into_inner();
+        assert!(rb.eq_slice(&[3, 4]));
+
+        // A limit larger than the contents reads everything.
+        let mut take = rb.take(10);
+        let mut buf = Vec::new();
+        take.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, [3, 4]);
+        assert_eq!(take.limit(), 8);
+    }
+
+    #[test]
+    fn ringbuf_event_handler() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let handler_events = Arc::clone(&events);
+
+        let mut rb = RingBuf::with_capacity(4);
+        rb.set_event_handler(Box::new(move |event| handler_events.lock().unwrap().push(event)));
+
+        for _ in 0..2 {
+            rb.write_all(&[0, 1]).unwrap();
+            rb.write_all(&[2, 3]).unwrap();
+            // Already full, so no second BecameFull.
+            assert_eq!(rb.write(&[4]).unwrap(), 0);
+            rb.read_exact(&mut [0u8; 3]).unwrap();
+            rb.read_exact(&mut [0u8; 1]).unwrap();
+            // Already empty, so no second BecameEmpty.
+            assert_eq!(rb.read(&mut [0u8; 1]).unwrap(), 0);
+        }
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            [BufEvent::BecameFull, BufEvent::BecameEmpty, BufEvent::BecameFull, BufEvent::BecameEmpty]
+        );
+    }
+
+    #[test]
+    fn ringbuf_matches_at() {
+        let mut rb = with_offset(6, 4);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+
+        // The match spans the wrap: [0, 1, 2] is at the end of the underlying buffer.
+        assert!(rb.matches_at(1, &[1, 2, 3]));
+        assert!(rb.matches_at(3, &[3, 4]));
+        assert!(rb.matches_at(5, &[]));
+        assert!(!rb.matches_at(1, &[1, 2, 4]));
+
+        // Near-misses running off the end of the buffered data.
+        assert!(!rb.matches_at(3, &[3, 4, 5]));
+        assert!(!rb.matches_at(6, &[]));
+        assert!(!rb.matches_at(usize::MAX, &[0]));
+    }
+
+    #[test]
+    fn ringbuf_from_vec_reuse() {
+        let mut v = Vec::with_capacity(RingBuf::backing_len(8));
+        v.extend_from_slice(&[1, 2, 3]);
+        let ptr = v.as_ptr();
+
+        let mut rb = RingBuf::from_vec_reuse(v, 8);
+        assert_eq!(rb.capacity(), 8);
         assert!(rb.is_empty());
+        rb.write_all(&[4, 5]).unwrap();
+        assert!(rb.eq_slice(&[4, 5]));
 
-        buf = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
-        assert_eq!(rb.write(&buf).unwrap(), 5);
+        let v = rb.into_inner();
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.len(), RingBuf::backing_len(8));
 
+        // A vector that's too small gets reallocated.
+        let rb = RingBuf::from_vec_reuse(vec![9; 2], 5);
         assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+        assert!(rb.is_empty());
+        assert_eq!(rb.into_inner(), [0; 6]);
+    }
 
-        buf = [0u8; 10];
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]);
+    #[test]
+    fn ringbuf_from_vec_reuse_spare_capacity() {
+        let mut v = Vec::with_capacity(32);
+        v.extend_from_slice(&[1, 2, 3]);
+        let ptr = v.as_ptr();
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 0);
+        // The whole allocation is used instead of shrinking it to the requested capacity.
+        let mut rb = RingBuf::from_vec_reuse(v, 8);
+        assert_eq!(rb.capacity(), 31);
         assert!(rb.is_empty());
+        rb.write_all(&[4; 31]).unwrap();
+        assert!(rb.is_full());
+
+        let v = rb.into_inner();
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(v.len(), 32);
     }
 
     #[test]
-    fn ringbuf_wrapped_read_write() {
-        let mut rb = RingBuf::with_capacity(5);
+    fn ringbuf_read_progress() {
+        let mut rb = with_offset(4, 3);
+        let mut buf = [0u8; 7];
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 0);
+        rb.write_all(&[0, 1, 2]).unwrap();
+        let filled = rb.read_progress(&mut buf, 0);
+        assert_eq!(filled, 3);
+        assert_eq!(rb.read_progress(&mut buf, filled), 3);
+
+        // Refill and finish the read.
+        rb.write_all(&[3, 4, 5, 6]).unwrap();
+        let filled = rb.read_progress(&mut buf, filled);
+        assert_eq!(filled, 7);
+        assert_eq!(buf, [0, 1, 2, 3, 4, 5, 6]);
         assert!(rb.is_empty());
 
-        let mut buf = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
-        assert_eq!(rb.write(&mut buf).unwrap(), 5);
+        assert_eq!(rb.read_progress(&mut buf, 7), 7);
+        assert_eq!(rb.read_available(&mut buf), 0);
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+    #[test]
+    fn ringbuf_try_extend() {
+        let mut rb = with_offset(4, 2);
+        rb.try_extend(vec![0, 1]).unwrap();
 
-        let mut buf = [0u8; 3];
+        // Filling the ring buffer exactly succeeds, wrapping around the end.
+        rb.try_extend([2, 3].iter().copied()).unwrap();
+        assert!(rb.is_full());
+        assert!(rb.eq_slice(&[0, 1, 2, 3]));
+        rb.try_extend(std::iter::empty()).unwrap();
 
-        assert_eq!(rb.peek(&mut buf).unwrap(), 3);
-        assert_eq!(buf, [0, 1, 2]);
+        rb.advance_read_pos(1).unwrap();
+        let mut iter = 4..8;
+        let err = rb.try_extend(&mut iter).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::WriteZero);
+        assert_eq!(err.get_ref().unwrap().downcast_ref::<RingError>(), Some(&RingError::Full));
+        assert!(rb.eq_slice(&[1, 2, 3, 4]));
+        assert_eq!(iter.next(), Some(6));
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+    #[test]
+    fn ringbuf_snapshot_restore() {
+        let mut rb = with_offset(4, 3);
+        rb.write_all(&[0, 1, 2]).unwrap();
+        assert!(!rb.is_contiguous());
+        let snapshot = rb.snapshot();
 
-        buf = [0u8; 3];
-        assert_eq!(rb.read(&mut buf).unwrap(), 3);
-        assert_eq!(buf, [0, 1, 2]);
+        let mut original = with_offset(4, 3);
+        original.write_all(&[0, 1, 2]).unwrap();
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 2);
-        assert!(!rb.is_empty());
+        rb.read_exact(&mut [0u8; 2]).unwrap();
+        rb.write_all(&[3, 4, 5]).unwrap();
+        rb.restore(&snapshot);
+        assert!(rb.structural_eq(&original));
+        assert_eq!(offsets(&rb), offsets(&original));
+        assert_eq!(rb.as_slices(), original.as_slices());
 
-        let mut buf = [9, 8, 7, 6, 5, 4, 3, 2, 1, 0];
-        assert_eq!(rb.write(&mut buf).unwrap(), 3);
+        // Restoring into a ring buffer with another capacity reallocates.
+        let mut other = RingBuf::with_capacity(2);
+        other.restore(&snapshot);
+        assert!(other.structural_eq(&original));
+        assert_eq!(other.as_slices(), original.as_slices());
+        assert_eq!(other.snapshot(), snapshot);
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+    #[test]
+    fn ringbuf_write_from_read_into() {
+        let mut rb = with_offset(4, 3);
 
-        buf = [0u8; 10];
-        assert_eq!(rb.peek(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [3, 4, 9, 8, 7, 0, 0, 0, 0, 0]);
+        assert_eq!(rb.write_from([0u8, 1]), 2);
+        assert_eq!(rb.write_from(vec![2, 3, 4]), 2);
+        assert_eq!(rb.write_from(&[5][..]), 0);
+        assert!(rb.is_full());
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+        let mut array = [0u8; 3];
+        assert_eq!(rb.read_into(&mut array), 3);
+        assert_eq!(array, [0, 1, 2]);
 
-        buf = [0u8; 10];
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [3, 4, 9, 8, 7, 0, 0, 0, 0, 0]);
+        let mut vec = vec![0u8; 3];
+        assert_eq!(rb.read_into(&mut vec), 1);
+        assert_eq!(vec, [3, 0, 0]);
+        assert_eq!(rb.read_into(&mut vec), 0);
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 0);
-        assert!(rb.is_empty());
+    #[test]
+    fn ringbuf_read_string() {
+        let mut rb = with_offset(8, 6);
+        rb.write_all("aé€b".as_bytes()).unwrap();
+
+        // "é" is split by the end of the underlying buffer.
+        assert_eq!(rb.as_slices().0.len(), 3);
+        assert_eq!(rb.read_string(6).unwrap(), "aé€");
+
+        let err = rb.read_string(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert!(rb.eq_slice(b"b"));
+
+        // Cutting "é" in half isn't valid UTF-8.
+        rb.write_all("é".as_bytes()).unwrap();
+        let err = rb.read_string(2).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(rb.len(), 3);
+        assert_eq!(rb.read_string(3).unwrap(), "bé");
     }
 
     #[test]
-    fn ringbuf_clear() {
-        let mut rb: RingBuf = vec![5, 4, 3, 2, 1].into();
+    fn ringbuf_window_eq() {
+        // The underlying buffer of `a` ends after "abc", that of `b` after "xyab".
+        let mut a = with_offset(7, 5);
+        a.write_all(b"abcdefg").unwrap();
+        let mut b = with_offset(7, 4);
+        b.write_all(b"xyabcde").unwrap();
+        let c: RingBuf = b"cdefg".to_vec().into();
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 5);
-        assert!(!rb.is_empty());
+        // Neither window straddles the end of its underlying buffer.
+        assert!(a.window_eq(3, &c, 1, 3));
+        // Only the window of `a` does.
+        assert!(a.window_eq(2, &c, 0, 4));
+        // Both windows do, at different points.
+        assert!(a.window_eq(0, &b, 2, 5));
+        assert!(!a.window_eq(0, &b, 1, 5));
 
-        rb.clear();
+        assert!(a.window_eq(7, &b, 7, 0));
+        assert!(!a.window_eq(3, &b, 2, 5));
+        assert!(!a.window_eq(0, &b, 3, 5));
+        assert!(!a.window_eq(usize::MAX, &b, 0, 1));
+    }
 
-        assert_eq!(rb.capacity(), 5);
-        assert_eq!(rb.len(), 0);
-        assert!(rb.is_empty());
+    #[test]
+    fn ringbuf_growth_factor() {
+        let mut rb = RingBuf::with_policy(10, FullPolicy::Grow);
+        rb.write_all(&[0; 11]).unwrap();
+        assert_eq!(rb.capacity(), 20);
+
+        let mut rb = RingBuf::with_policy(10, FullPolicy::Grow);
+        rb.set_growth_factor(1.5);
+        rb.write_all(&[0; 11]).unwrap();
+        assert_eq!(rb.capacity(), 15);
+        rb.write_all(&[0; 5]).unwrap();
+        // 15 * 1.5 = 22.5 is rounded up.
+        assert_eq!(rb.capacity(), 23);
+
+        // The needed capacity wins if it's larger.
+        rb.set_growth_factor(3.0);
+        rb.reserve(100);
+        assert_eq!(rb.capacity(), 116);
+        rb.reserve(101);
+        assert_eq!(rb.capacity(), 348);
     }
 
     #[test]
-    fn ringbuf_peek_read_empty() {
-        let mut rb = RingBuf::with_capacity(10);
+    #[should_panic(expected = "growth factor must be greater than 1")]
+    fn ringbuf_growth_factor_invalid() {
+        RingBuf::with_capacity(4).set_growth_factor(1.0);
+    }
 
-        let mut buf = [0u8; 10];
-        assert_eq!(rb.peek(&mut buf).unwrap(), 0);
-        assert_eq!(rb.read(&mut buf).unwrap(), 0);
+    #[test]
+    #[should_panic(expected = "growth factor must be greater than 1 and finite")]
+    fn ringbuf_growth_factor_infinite() {
+        RingBuf::with_capacity(4).set_growth_factor(f32::INFINITY);
     }
 
     #[test]
-    fn ringbuf_peek_read_0_len_buf() {
-        let mut rb: RingBuf = vec![0, 1, 2].into();
+    #[should_panic(expected = "growth factor must be greater than 1 and finite")]
+    fn ringbuf_growth_factor_nan() {
+        RingBuf::with_capacity(4).set_growth_factor(f32::NAN);
+    }
 
-        let mut buf = [0u8; 0];
-        assert_eq!(rb.peek(&mut buf).unwrap(), 0);
-        assert_eq!(rb.read(&mut buf).unwrap(), 0);
+    #[test]
+    fn ringbuf_into_from_parts() {
+        let mut rb = with_offset(4, 3);
+        rb.write_all(&[0, 1, 2]).unwrap();
+        let (read_pos, write_pos) = offsets(&rb);
+
+        let (data, parts_read_pos, parts_write_pos) = rb.into_parts();
+        assert_eq!((parts_read_pos, parts_write_pos), (read_pos, write_pos));
+        assert_eq!(&*data, &[2, 0, 0, 0, 1]);
+
+        let rb = RingBuf::from_parts(data, read_pos, write_pos).unwrap();
+        assert_eq!(offsets(&rb), (read_pos, write_pos));
+        assert!(rb.eq_slice(&[0, 1, 2]));
+        assert_eq!(rb.capacity(), 4);
+
+        let data = rb.into_parts().0;
+        let err = RingBuf::from_parts(data.clone(), 5, 0).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidInput);
+        assert!(RingBuf::from_parts(data.clone(), 0, 5).is_err());
+        assert!(RingBuf::from_parts(Box::new([]), 0, 0).is_err());
+
+        // Equal positions give an empty ring buffer with both positions at the start.
+        let rb = RingBuf::from_parts(data, 3, 3).unwrap();
+        assert!(rb.is_empty());
+        assert_eq!(offsets(&rb), (0, 0));
+        assert_eq!(rb.available_contiguous_write(), 4);
     }
 
     #[test]
-    fn ringbuf_read_write_larger_then_capacity() {
-        let mut rb = RingBuf::with_capacity(5);
+    fn ringbuf_compacting_read() {
+        let mut rb = with_offset(8, 2);
+        rb.write_all(&[0, 1, 2, 3, 4, 5]).unwrap();
 
-        assert_eq!(rb.write(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]).unwrap(), 5);
+        // 3 bytes left is above the threshold of 2, so nothing moves.
+        assert_eq!(rb.compacting_read(&mut [0u8; 3]).unwrap(), 3);
+        assert_eq!(rb.contiguous_free_now(), 1);
 
-        let mut buf = [0u8; 10];
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [1, 2, 3, 4, 5, 0, 0, 0, 0, 0]);
+        // A remainder of 1 byte is moved to the start.
+        assert_eq!(rb.compacting_read(&mut [0u8; 2]).unwrap(), 2);
+        assert_eq!(rb.contiguous_free_now(), rb.remaining());
+        assert_eq!(offsets(&rb), (0, 1));
+        assert!(rb.eq_slice(&[5]));
+        assert_eq!(rb.rewindable(), 0);
 
-        assert_eq!(rb.write(&[6, 7, 8, 9, 10, 11, 12, 13, 14, 15]).unwrap(), 5);
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [6, 7, 8, 9, 10, 0, 0, 0, 0, 0]);
-        assert_eq!(rb.len(), 0);
+        // With a threshold of 0 it never compacts.
+        let mut rb = with_offset(8, 6);
+        rb.set_compact_threshold(0.0);
+        rb.write_all(&[0, 1, 2]).unwrap();
+        rb.compacting_read(&mut [0u8; 2]).unwrap();
+        assert_eq!(offsets(&rb), (8, 0));
+    }
+
+    #[test]
+    fn ringbuf_read_to_string_ring() {
+        let mut rb = with_offset(8, 7);
+        rb.write_all("€uro".as_bytes()).unwrap();
+        // "€" is split by the end of the underlying buffer.
+        assert_eq!(rb.as_slices().0.len(), 2);
+
+        let mut out = String::new();
+        assert_eq!(rb.read_to_string_ring(&mut out).unwrap(), 6);
+        assert_eq!(out, "€uro");
         assert!(rb.is_empty());
+        assert_eq!(rb.read_to_string_ring(&mut out).unwrap(), 0);
+
+        rb.write_all(&[b'a', 0xE2, 0x82]).unwrap();
+        let err = rb.read_to_string_ring(&mut out).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(out, "€uro");
+        assert_eq!(rb.len(), 3);
     }
 
     #[test]
-    fn ringbuf_read_write_buf_end() {
-        let mut rb = RingBuf::with_capacity(5);
+    fn ringbuf_peek_blocks() {
+        let mut rb = with_offset(10, 7);
+        rb.write_all(&[0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
 
-        assert_eq!(rb.write(&[1]).unwrap(), 1);
+        // The second block straddles the end of the underlying buffer,
+        // and [6, 7] doesn't make up a full block.
+        let iter = rb.peek_blocks::<3>();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.collect::<Vec<_>>(), [[0, 1, 2], [3, 4, 5]]);
 
-        let mut buf = [0u8; 10];
-        assert_eq!(rb.read(&mut buf).unwrap(), 1);
-        assert_eq!(buf, [1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(rb.peek_blocks::<1>().count(), 8);
+        assert_eq!(rb.peek_blocks::<9>().next(), None);
+        assert_eq!(rb.len(), 8);
+    }
 
-        assert_eq!(rb.write(&[0, 1, 2, 3, 4]).unwrap(), 5);
-        assert_eq!(rb.read(&mut buf).unwrap(), 5);
-        assert_eq!(buf, [0, 1, 2, 3, 4, 0, 0, 0, 0, 0]);
-        assert_eq!(rb.read_pos, 0);
-        assert_eq!(rb.write_pos, 0);
-        assert_eq!(rb.len(), 0);
+    #[test]
+    fn ringbuf_rewind_read_after_drain() {
+        let mut rb = RingBuf::with_capacity(5);
+        rb.write_all(&[0, 1, 2, 3, 4]).unwrap();
+
+        let mut buf = [0u8; 5];
+        rb.read_exact(&mut buf).unwrap();
         assert!(rb.is_empty());
+        assert_eq!(offsets(&rb), (5, 5));
+
+        assert_eq!(rb.rewind_read(), 5);
+        assert!(rb.eq_slice(&[0, 1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn ringbuf_event_handler_write_paths() {
+        use std::sync::{Arc, Mutex};
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut rb = RingBuf::with_capacity(8);
+        let handler_events = Arc::clone(&events);
+        rb.set_event_handler(Box::new(move |event| handler_events.lock().unwrap().push(event)));
+
+        let (number, text) = (123, "abcd");
+        write!(rb, "{}-{}", number, text).unwrap();
+        assert!(rb.is_full());
+        assert_eq!(*events.lock().unwrap(), [BufEvent::BecameFull]);
+
+        rb.clear();
+        assert_eq!(rb.write_reporting(&[0; 8]), (8, false));
+        rb.advance_read_pos(8).unwrap();
+        assert_eq!(
+            *events.lock().unwrap(),
+            [BufEvent::BecameFull, BufEvent::BecameEmpty, BufEvent::BecameFull, BufEvent::BecameEmpty]
+        );
+
+        // Dropping the oldest bytes to make room doesn't report the ring buffer becoming
+        // empty or full again.
+        events.lock().unwrap().clear();
+        rb.set_policy(FullPolicy::OverwriteOldest);
+        rb.write_all(&[0; 8]).unwrap();
+        rb.write_all(&[1; 8]).unwrap();
+        rb.write_all(&[2; 3]).unwrap();
+        assert_eq!(*events.lock().unwrap(), [BufEvent::BecameFull]);
+    }
+
+    #[test]
+    fn ringbuf_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<RingBuf>();
     }
 }